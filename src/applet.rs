@@ -9,11 +9,16 @@
 //! 1. **Animation**: Cat sprites are embedded in the binary at compile time.
 //!    A timer ticks ~30 times per second to advance the animation frame.
 //!    The actual animation speed (FPS) is scaled by the chosen metric
-//!    (CPU usage, frequency, or temperature).
+//!    (CPU usage, frequency, temperature, memory usage, network
+//!    throughput, disk I/O, GPU load, pressure stall information, load
+//!    average, power draw, a weighted mix of several of these, or the output of a
+//!    user-supplied command).
 //!
 //! 2. **Theme integration**: The cat sprite is recolored to match the
 //!    COSMIC desktop accent color. When the user changes their theme,
 //!    the sprites are re-recolored from the original embedded PNGs.
+//!    The theme and config files are watched for changes (`watcher.rs`)
+//!    and only re-read when they change.
 //!
 //! 3. **Popup**: Clicking the cat opens a popup showing per-core stats
 //!    with colored progress bars. The popup is created via the COSMIC
@@ -30,14 +35,27 @@ use cosmic::surface::action::{app_popup, destroy_popup};
 use cosmic::widget::{self, text};
 use cosmic::Element;
 
-use crate::config::{AnimationSource, Config};
+use crate::command::{CommandResult, CommandRunner, CommandSettings};
+use crate::config::{AnimationSource, Config, MixContribution, MixMode};
 use crate::constants::*;
-use crate::cpu::{CpuMonitor, CpuUsage};
-use crate::sysinfo::{CpuFrequency, CpuTemperature};
+use crate::cpu::{CpuBreakdown, CpuMonitor, CpuUsage};
+use crate::disk::DiskUsage;
+use crate::gpu::GpuInfo;
+use crate::loadavg::LoadAverage;
+use crate::memory::{kb_to_gib, MemoryInfo};
+use crate::metrics::{MetricSettings, MetricsMonitor, MetricsSample};
+use crate::network::{format_rate, mbps_to_bytes_per_sec, NetworkUsage};
+use crate::power::PowerUsage;
+use crate::pressure::PressureInfo;
+use crate::schedule::{self, Schedule, ScheduleAction};
+use crate::sleep::{SleepRule, SleepState};
+use crate::smoothing::Smoother;
+use crate::sysinfo::{CpuFrequency, CpuTemperature, SensorMonitor, SensorSample};
 use crate::theme;
+use crate::watcher::{ConfigChanges, ConfigWatcher};
 
 use image::RgbaImage;
-use std::collections::VecDeque;
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// Application ID — must match the `.desktop` entry filename so the COSMIC
@@ -59,12 +77,35 @@ pub enum Message {
     /// animation frame based on the current metric.
     AnimationTick,
 
-    /// Fired every 500ms.  Reads new CPU usage, frequency, and temperature
-    /// data from the OS and updates the smoothed values.
-    CpuUpdate,
+    /// Sent as soon as the CPU monitor thread has a new sample (every
+    /// `Config::sample_interval_ms`).  Updates the smoothed CPU value.
+    CpuSample(CpuUsage),
 
-    /// Fired every 500ms.  Reloads the config file and checks whether the
-    /// desktop theme has changed (so we can recolor sprites).
+    /// Sent when the metrics monitor thread has read the memory, network
+    /// and other metrics in use (every `Config::sample_interval_ms`).
+    MetricsSample(Box<MetricsSample>),
+
+    /// Sent when the sensor monitor thread has read CPU frequency and
+    /// temperature (every `SENSOR_SAMPLE_INTERVAL`).  Updates their
+    /// smoothed values.
+    SensorSample(SensorSample),
+
+    /// Sent when the command runner thread has run the custom command
+    /// (every `Config::command_interval_secs`), with its output or error.
+    CommandResult(Option<CommandResult>),
+
+    /// Sent by cosmic-config when stored settings have changed, with the
+    /// repaired config and what was wrong with it.
+    ConfigUpdated(Box<Config>, Vec<String>),
+
+    /// Sent when config.json or the COSMIC theme files have changed on
+    /// disk.  Reloads whichever changed (recoloring sprites for a new
+    /// theme); config.json only matters if the store couldn't be opened.
+    ConfigChanged(ConfigChanges),
+
+    /// Fired every `Config::config_check_interval_ms`, only if the files
+    /// couldn't be watched.  Reloads the theme (and config.json, without
+    /// the store) unconditionally.
     ConfigCheck,
 
     /// The compositor closed our popup window (e.g. user clicked elsewhere).
@@ -74,6 +115,13 @@ pub enum Message {
     /// runtime can process it.
     Surface(cosmic::surface::Action),
 
+    /// User picked a profile in the popup's profile switcher.
+    SwitchProfile(String),
+
+    /// Fired every `SCHEDULE_CHECK_INTERVAL` while any schedules are set.
+    /// Starts or ends schedules as the clock passes their times.
+    ScheduleCheck,
+
     /// User clicked the "Settings" button in the popup.
     OpenSettings,
 }
@@ -240,30 +288,80 @@ pub struct RunkatApplet {
     // --- Animation state ---
     /// Which run-cycle frame we're currently showing (0..9)
     current_frame: u8,
-    /// True when the monitored metric is below the sleep threshold
-    is_sleeping: bool,
+    /// Whether the cat is asleep — the metric is below the sleep threshold,
+    /// with hysteresis and a minimum dwell time so it doesn't flicker
+    sleep_state: SleepState,
     /// When we last advanced the frame — used to control actual FPS
     last_frame_time: std::time::Instant,
 
+    // --- Schedules ---
+    /// The schedule in effect right now, if any
+    active_schedule: Option<Schedule>,
+    /// The config as stored, while the active schedule's profile is
+    /// applied over it (only in memory, so a restart during the schedule
+    /// still finds the user's own profile).  Restored when the schedule ends,
+    /// unless the user picks a profile meanwhile.
+    config_before_schedule: Option<Config>,
+
     // --- System metrics ---
-    /// Background thread that reads CPU usage via systemstat
+    /// Background thread that reads CPU usage from /proc/stat
     cpu_monitor: CpuMonitor,
     /// Latest CPU usage snapshot (aggregate + per-core percentages)
     cpu_usage: CpuUsage,
+    /// Background thread that reads CPU frequency and temperature
+    sensor_monitor: SensorMonitor,
     /// Latest CPU frequency readings from sysfs
     cpu_frequency: CpuFrequency,
     /// Latest CPU temperature readings from hwmon
     cpu_temperature: CpuTemperature,
-
-    // --- CPU smoothing ---
-    /// Rolling window of recent CPU samples for averaging
-    cpu_samples: VecDeque<f32>,
-    /// The smoothed (averaged) CPU percentage shown in the UI
-    smoothed_cpu: f32,
+    /// Background thread that reads the memory, network, disk, GPU,
+    /// pressure, load average and power metrics in use
+    metrics_monitor: MetricsMonitor,
+    /// Latest RAM / swap readings from /proc/meminfo
+    memory: MemoryInfo,
+    /// Latest per-interface network throughput
+    network: NetworkUsage,
+    /// Latest per-disk throughput and utilisation
+    disk: DiskUsage,
+    /// Latest per-card GPU readings from DRM sysfs
+    gpu: GpuInfo,
+    /// Latest pressure stall figures from /proc/pressure
+    pressure: PressureInfo,
+    /// Latest load averages from /proc/loadavg
+    load_average: LoadAverage,
+    /// Latest RAPL domain power and battery state
+    power: PowerUsage,
+    /// Background thread that runs the user's custom command
+    command_runner: CommandRunner,
+    /// Latest custom command result, or None if it hasn't run yet
+    command_result: Option<CommandResult>,
+
+    // --- Smoothing ---
+    /// Smoothed CPU percentage shown in the UI and driving the cat
+    cpu_smoother: Smoother,
+    /// Smoothed average CPU frequency in MHz (for the sleep threshold)
+    frequency_smoother: Smoother,
+    /// Smoothed average CPU frequency as a % of max (for the speed)
+    frequency_pct_smoother: Smoother,
+    /// Smoothed hottest CPU temperature in degrees C
+    temperature_smoother: Smoother,
 
     // --- Configuration ---
-    /// User preferences loaded from config.json
+    /// User preferences loaded from the config store
     config: Config,
+    /// Whether the config is in the cosmic-config store (and followed
+    /// through its change notifications) rather than config.json
+    config_in_store: bool,
+    /// Watches config.json and the theme files, or None if watching
+    /// couldn't be set up (then they're polled instead)
+    config_watcher: Option<ConfigWatcher>,
+    /// Change counts as of the last `ConfigChanged`, to tell what changed
+    config_changes: ConfigChanges,
+    /// What was wrong with the config when it was last read (shown in the
+    /// popup); empty if nothing
+    config_problems: Vec<String>,
+    /// Where that read kept a copy of the rejected config file, if it did
+    config_backup: Option<PathBuf>,
 
     // --- Popup ---
     /// Window ID of the open popup, or None if closed
@@ -284,8 +382,9 @@ pub struct RunkatApplet {
 // ---------------------------------------------------------------------------
 
 impl cosmic::Application for RunkatApplet {
-    /// Use a single-threaded executor since we do our heavy work on a
-    /// background OS thread (CpuMonitor) rather than async tasks.
+    /// Use a single-threaded executor since we do our heavy work on
+    /// background OS threads (CpuMonitor, MetricsMonitor, ...) rather than
+    /// async tasks.
     type Executor = cosmic::SingleThreadExecutor;
     type Flags = ();
     type Message = Message;
@@ -303,7 +402,8 @@ impl cosmic::Application for RunkatApplet {
     /// Called once when the applet starts.  Sets up sprites, starts the
     /// CPU monitoring thread, reads initial sensor data, and loads config.
     fn init(core: Core, _flags: Self::Flags) -> (Self, Task<Self::Message>) {
-        let config = Config::load();
+        let config_in_store = Config::store().is_some();
+        let (config, config_problems, config_backup) = Config::load_checked();
         let mut sprites = SpriteCache::load();
 
         // Read the COSMIC theme's accent color and recolor sprites to match
@@ -311,27 +411,66 @@ impl cosmic::Application for RunkatApplet {
         sprites.update_colors(theme_colors.foreground);
 
         // Start the background CPU monitoring thread
-        let cpu_monitor = CpuMonitor::new();
-        cpu_monitor.start(CPU_SAMPLE_INTERVAL);
+        let mut cpu_monitor = CpuMonitor::new();
+        cpu_monitor.set_cgroup(&config.cgroup_path);
+        cpu_monitor.start(config.sample_interval());
+
+        // Start the background sensor thread (frequency and temperature)
+        let sensor_monitor = SensorMonitor::new();
+        sensor_monitor.start(SENSOR_SAMPLE_INTERVAL);
+
+        // Start the metrics thread (only reads the metrics in use)
+        let metrics_monitor = MetricsMonitor::new();
+        metrics_monitor.configure(metric_settings(&config));
+
+        // Start the custom command thread (only runs if it's in use)
+        let command_runner = CommandRunner::new();
+        command_runner.configure(command_settings(&config));
 
-        let applet = Self {
+        // Watch for config and theme changes, falling back to polling
+        let config_watcher = ConfigWatcher::new()
+            .map_err(|e| tracing::warn!("Can't watch config files, polling instead: {}", e))
+            .ok();
+
+        let mut applet = Self {
             core,
             sprites,
             current_frame: 0,
-            is_sleeping: true, // start sleeping until we get real data
+            sleep_state: SleepState::new(std::time::Instant::now()), // until we get real data
             last_frame_time: std::time::Instant::now(),
+            active_schedule: None,
+            config_before_schedule: None,
             cpu_monitor,
             cpu_usage: CpuUsage::default(),
-            cpu_frequency: CpuFrequency::read(),
-            cpu_temperature: CpuTemperature::read(),
-            cpu_samples: VecDeque::with_capacity(CPU_SAMPLE_COUNT),
-            smoothed_cpu: 0.0,
+            sensor_monitor,
+            cpu_frequency: CpuFrequency::default(),
+            cpu_temperature: CpuTemperature::default(),
+            metrics_monitor,
+            memory: MemoryInfo::default(),
+            network: NetworkUsage::default(),
+            disk: DiskUsage::default(),
+            gpu: GpuInfo::default(),
+            pressure: PressureInfo::default(),
+            load_average: LoadAverage::default(),
+            power: PowerUsage::default(),
+            command_runner,
+            command_result: None,
+            cpu_smoother: Smoother::new(),
+            frequency_smoother: Smoother::new(),
+            frequency_pct_smoother: Smoother::new(),
+            temperature_smoother: Smoother::new(),
             config,
+            config_in_store,
+            config_watcher,
+            config_changes: ConfigChanges::default(),
+            config_problems: config_problems.iter().map(ToString::to_string).collect(),
+            config_backup,
             popup: None,
             accent_color: theme_colors.foreground,
             theme_is_dark: theme_colors.is_dark,
             tooltip: String::from("RunKat"),
         };
+        applet.update_schedule();
 
         // Task::none() means no asynchronous work to do at startup
         (applet, Task::none())
@@ -349,33 +488,26 @@ impl cosmic::Application for RunkatApplet {
             // ---------------------------------------------------------------
             // ANIMATION TICK (~30 times per second)
             // ---------------------------------------------------------------
-            // 1. Read the relevant metric based on user's chosen source
-            // 2. Determine if the cat should be sleeping (metric below threshold)
+            // 1. Look up the relevant metric based on user's chosen source
+            // 2. Determine if the cat should be sleeping (metric below threshold,
+            //    with hysteresis and minimum dwell time)
             // 3. If awake, advance the animation frame at the calculated FPS
             Message::AnimationTick => {
-                let (metric, sleeping) = match self.config.animation_source {
-                    AnimationSource::CpuUsage => {
-                        let m = self.smoothed_cpu;
-                        // Sleep when CPU usage is below the configured threshold
-                        (m, m < self.config.sleep_threshold_cpu)
-                    }
-                    AnimationSource::Frequency => {
-                        // Use percentage for animation speed, but compare
-                        // absolute MHz against threshold for sleep decision
-                        let metric = self.cpu_frequency.average_percentage();
-                        let avg_mhz = self.cpu_frequency.average_mhz() as f32;
-                        (metric, avg_mhz < self.config.sleep_threshold_freq)
-                    }
-                    AnimationSource::Temperature => {
-                        // Use percentage of critical temp for animation speed,
-                        // but compare actual degrees for sleep decision
-                        let actual = self.cpu_temperature.max_temp();
-                        let metric = self.cpu_temperature.percentage();
-                        (metric, actual < self.config.sleep_threshold_temp)
-                    }
+                // Every source maps onto 0-100% for the animation speed, but
+                // the sleep decision compares the source's natural unit
+                // (MHz, degrees, KB/s, ...) against its threshold
+                let source = self.config.animation_source;
+                let metric = self.source_percentage(source);
+                let rule = SleepRule {
+                    threshold: self.config.current_threshold(),
+                    hysteresis: self.config.sleep_hysteresis_pct / 100.0,
+                    min_dwell: self.config.sleep_min_dwell(),
                 };
-
-                self.is_sleeping = sleeping;
+                let sleeping = self.sleep_state.update(
+                    self.threshold_value(source),
+                    &rule,
+                    std::time::Instant::now(),
+                ) || self.quiet_hours();
 
                 // Only advance animation frames when the cat is awake
                 if !sleeping {
@@ -393,51 +525,94 @@ impl cosmic::Application for RunkatApplet {
             }
 
             // ---------------------------------------------------------------
-            // CPU DATA UPDATE (every 500ms)
+            // CPU DATA UPDATE (whenever the monitor thread has a sample)
             // ---------------------------------------------------------------
-            Message::CpuUpdate => {
-                // Get the latest snapshot from the background monitoring thread
-                self.cpu_usage = self.cpu_monitor.current();
-
-                // Smooth the aggregate CPU percentage using a rolling average.
-                // This prevents the animation from jittering on brief spikes.
-                let raw = self.cpu_usage.aggregate;
-                self.cpu_samples.push_back(raw);
-                if self.cpu_samples.len() > CPU_SAMPLE_COUNT {
-                    self.cpu_samples.pop_front(); // remove oldest sample
-                }
-                self.smoothed_cpu = if self.cpu_samples.is_empty() {
-                    raw
-                } else {
-                    self.cpu_samples.iter().sum::<f32>() / self.cpu_samples.len() as f32
+            Message::CpuSample(usage) => {
+                // The snapshot the background monitoring thread just sent
+                self.cpu_usage = usage;
+
+                // Smooth the aggregate CPU percentage with the configured
+                // filter.  This prevents the animation from jittering on
+                // brief spikes.  With a cgroup configured, only that
+                // workload counts (and an unreadable cgroup counts as idle;
+                // the popup says why).
+                let raw = match &self.cpu_usage.cgroup {
+                    Some(cgroup) => cgroup.percent.unwrap_or(0.0),
+                    None => self.cpu_usage.aggregate,
                 };
-
-                // Also refresh frequency and temperature (read directly from sysfs)
-                self.cpu_frequency = CpuFrequency::read();
-                self.cpu_temperature = CpuTemperature::read();
+                self.cpu_smoother
+                    .push(raw, self.config.smoothing_filter(AnimationSource::CpuUsage));
 
                 // Update the hover tooltip text
                 self.tooltip = self.make_tooltip();
             }
 
             // ---------------------------------------------------------------
-            // CONFIG / THEME CHECK (every 500ms)
+            // METRICS UPDATE (whenever the metrics thread has a sample)
+            // ---------------------------------------------------------------
+            Message::MetricsSample(sample) => {
+                let MetricsSample { memory, network, disk, gpu, pressure, load_average, power } =
+                    *sample;
+                self.memory = memory;
+                self.network = network;
+                self.disk = disk;
+                self.gpu = gpu;
+                self.pressure = pressure;
+                self.load_average = load_average;
+                self.power = power;
+                self.tooltip = self.make_tooltip();
+            }
+
+            // ---------------------------------------------------------------
+            // SENSOR DATA UPDATE (whenever the sensor thread has a sample)
+            // ---------------------------------------------------------------
+            Message::SensorSample(sample) => {
+                self.cpu_frequency = sample.frequency;
+                self.cpu_temperature = sample.temperature;
+
+                // Smooth frequency and temperature the same way as CPU usage
+                let filter = self.config.smoothing_filter(AnimationSource::Frequency);
+                self.frequency_smoother.push(self.cpu_frequency.average_mhz() as f32, filter);
+                self.frequency_pct_smoother.push(self.cpu_frequency.average_percentage(), filter);
+                let filter = self.config.smoothing_filter(AnimationSource::Temperature);
+                self.temperature_smoother.push(self.cpu_temperature.max_temp(), filter);
+                self.tooltip = self.make_tooltip();
+            }
+
+            // ---------------------------------------------------------------
+            // CUSTOM COMMAND RESULT (whenever the runner thread has one)
+            // ---------------------------------------------------------------
+            Message::CommandResult(result) => {
+                self.command_result = result;
+                self.tooltip = self.make_tooltip();
+            }
+
+            // ---------------------------------------------------------------
+            // CONFIG / THEME CHANGES
             // ---------------------------------------------------------------
+            Message::ConfigUpdated(config, problems) => {
+                // Settings changes take effect without restarting the applet
+                self.config_problems = problems;
+                self.config_backup = None;
+                self.use_stored_config(*config);
+            }
+
+            Message::ConfigChanged(changes) => {
+                if changes.config != self.config_changes.config && !self.config_in_store {
+                    self.reload_config();
+                }
+                if changes.theme != self.config_changes.theme {
+                    self.reload_theme();
+                }
+                self.config_changes = changes;
+            }
+
+            // Polling fallback (every config_check_interval_ms)
             Message::ConfigCheck => {
-                // Reload config from disk so settings changes take effect
-                // without restarting the applet
-                self.config = Config::load();
-
-                // Check if the desktop theme accent color has changed
-                let theme_colors = theme::get_cosmic_theme_colors();
-                if theme_colors.foreground != self.accent_color
-                    || theme_colors.is_dark != self.theme_is_dark
-                {
-                    self.accent_color = theme_colors.foreground;
-                    self.theme_is_dark = theme_colors.is_dark;
-                    // Re-tint all sprites to match the new accent color
-                    self.sprites.update_colors(theme_colors.foreground);
+                if !self.config_in_store {
+                    self.reload_config();
                 }
+                self.reload_theme();
             }
 
             // ---------------------------------------------------------------
@@ -457,6 +632,16 @@ impl cosmic::Application for RunkatApplet {
                 ));
             }
 
+            // ---------------------------------------------------------------
+            // PROFILES
+            // ---------------------------------------------------------------
+            Message::SwitchProfile(name) => self.switch_profile(&name),
+
+            // ---------------------------------------------------------------
+            // SCHEDULES (every SCHEDULE_CHECK_INTERVAL)
+            // ---------------------------------------------------------------
+            Message::ScheduleCheck => self.update_schedule(),
+
             // ---------------------------------------------------------------
             // SETTINGS
             // ---------------------------------------------------------------
@@ -465,15 +650,20 @@ impl cosmic::Application for RunkatApplet {
             // can briefly block while setting up the child process.
             Message::OpenSettings => {
                 std::thread::spawn(|| {
+                    // Both pick up the instance from the environment, so
+                    // they edit this applet's config
+                    let instance = Config::instance().unwrap_or_default();
                     // Try unified settings hub first, fall back to standalone
                     let unified = std::process::Command::new("cosmic-applet-settings")
                         .arg(APP_ID)
+                        .env(INSTANCE_ENV, instance)
                         .spawn();
                     if unified.is_err() {
                         let exe = std::env::current_exe()
                             .unwrap_or_else(|_| "cosmic-runkat".into());
                         if let Err(e) = std::process::Command::new(exe)
                             .arg("--settings-standalone")
+                            .env(INSTANCE_ENV, instance)
                             .spawn()
                         {
                             eprintln!("Failed to launch settings: {e}");
@@ -486,19 +676,57 @@ impl cosmic::Application for RunkatApplet {
         Task::none()
     }
 
-    /// Register timer subscriptions.  These fire periodically and generate
-    /// the messages that drive the applet's update loop.
+    /// Register subscriptions.  Timers fire periodically; the CPU
+    /// subscription fires whenever the monitor thread sends a sample.
+    /// Together they generate the messages that drive the update loop.
     fn subscription(&self) -> cosmic::iced::Subscription<Self::Message> {
         cosmic::iced::Subscription::batch([
             // ~30 FPS animation tick
             cosmic::iced::time::every(Duration::from_millis(33))
                 .map(|_| Message::AnimationTick),
-            // CPU / sensor data refresh
-            cosmic::iced::time::every(Duration::from_millis(500))
-                .map(|_| Message::CpuUpdate),
-            // Config & theme change detection
-            cosmic::iced::time::every(Duration::from_millis(500))
-                .map(|_| Message::ConfigCheck),
+            // CPU / sensor / metrics data refresh, pushed by the monitor threads
+            watch_subscription("cpu-samples", self.cpu_monitor.subscribe(), Message::CpuSample),
+            watch_subscription(
+                "sensor-samples",
+                self.sensor_monitor.subscribe(),
+                Message::SensorSample,
+            ),
+            watch_subscription("metrics-samples", self.metrics_monitor.subscribe(), |sample| {
+                Message::MetricsSample(Box::new(sample))
+            }),
+            watch_subscription(
+                "command-results",
+                self.command_runner.subscribe(),
+                Message::CommandResult,
+            ),
+            // Stored settings changes, from cosmic-config
+            if self.config_in_store {
+                self.core.watch_config::<Config>(Config::store_id()).map(|update| {
+                    let (config, problems) = Config::checked(update.config, update.errors);
+                    Message::ConfigUpdated(
+                        Box::new(config),
+                        problems.iter().map(ToString::to_string).collect(),
+                    )
+                })
+            } else {
+                cosmic::iced::Subscription::none()
+            },
+            // Theme (and config.json) change detection
+            match &self.config_watcher {
+                Some(watcher) => watch_subscription(
+                    "config-changes",
+                    watcher.subscribe(),
+                    Message::ConfigChanged,
+                ),
+                None => cosmic::iced::time::every(self.config.config_check_interval())
+                    .map(|_| Message::ConfigCheck),
+            },
+            // Schedule changes, only while there are schedules to follow
+            if self.config.schedules.is_empty() {
+                cosmic::iced::Subscription::none()
+            } else {
+                cosmic::iced::time::every(SCHEDULE_CHECK_INTERVAL).map(|_| Message::ScheduleCheck)
+            },
         ])
     }
 
@@ -506,7 +734,7 @@ impl cosmic::Application for RunkatApplet {
     /// COSMIC panel — a small animated cat image with an optional CPU% label.
     fn view(&self) -> Element<'_, Message> {
         // Get the current cat frame as a renderable image handle
-        let handle = self.sprites.frame_handle(self.current_frame, self.is_sleeping);
+        let handle = self.sprites.frame_handle(self.current_frame, self.is_sleeping());
 
         // Ask the COSMIC applet framework for the suggested icon size
         let suggested = self.core.applet.suggested_size(true);
@@ -519,9 +747,9 @@ impl cosmic::Application for RunkatApplet {
         // Optionally show a "42%" label next to the cat (CPU mode only)
         let content: Element<Message> = if self.config.show_percentage
             && self.config.animation_source == AnimationSource::CpuUsage
-            && !self.is_sleeping
+            && !self.is_sleeping()
         {
-            let pct_num = cosmic::iced::widget::text(format!("{:.0}", self.smoothed_cpu))
+            let pct_num = cosmic::iced::widget::text(format!("{:.0}", self.cpu_smoother.value()))
                 .font(cosmic::iced::Font::MONOSPACE)
                 .size(11);
             let pct_sym = cosmic::iced::widget::text("%")
@@ -558,15 +786,34 @@ impl cosmic::Application for RunkatApplet {
                             state.popup = Some(new_id);
 
                             // Size the popup to fit the number of CPU cores
-                            let core_count = state.cpu_usage.per_core.len().max(
-                                state.cpu_frequency.per_core.len().max(
-                                    state.cpu_temperature.per_core.len(),
-                                ),
-                            );
+                            // (or the interface / disk / GPU rows of other modes)
+                            let core_count = state
+                                .cpu_usage
+                                .per_core
+                                .len()
+                                .max(state.cpu_frequency.per_core.len())
+                                .max(state.cpu_temperature.per_core.len())
+                                .max(state.network.interfaces.len() * 2)
+                                .max(state.disk.disks.len() * 3)
+                                .max(state.gpu.cards.len() * 4)
+                                .max(
+                                    state.power.domains.len()
+                                        + state.power.batteries.len() * 2,
+                                );
                             let visible_rows =
                                 (core_count as u32 + 1).min(POPUP_MAX_ROWS);
-                            let popup_height =
-                                POPUP_BASE_HEIGHT + visible_rows * POPUP_ROW_HEIGHT;
+                            // Plus the config problem banner and profile
+                            // switcher, if any
+                            let problem_rows = match state.config_problems.len() {
+                                0 => 0,
+                                n => n as u32 + 1 + u32::from(state.config_backup.is_some()),
+                            };
+                            let profile_rows =
+                                if state.config.profiles.is_empty() { 0 } else { 2 }
+                                    + u32::from(state.active_schedule.is_some());
+                            let popup_height = POPUP_BASE_HEIGHT
+                                + (visible_rows + problem_rows + profile_rows)
+                                    * POPUP_ROW_HEIGHT;
 
                             // Get the main window ID so the popup can anchor to it.
                             // If unavailable (shouldn't happen), fall back gracefully.
@@ -636,19 +883,233 @@ impl cosmic::Application for RunkatApplet {
 // ---------------------------------------------------------------------------
 
 impl RunkatApplet {
+    /// Re-read config.json and apply settings the background threads use.
+    /// Invalid fields are repaired; a file that doesn't parse at all (e.g.
+    /// half-written) is ignored.  Either way the problems are shown in the
+    /// popup.
+    fn reload_config(&mut self) {
+        match Config::reload() {
+            Ok((config, problems, backup)) => {
+                self.config_problems = problems.iter().map(ToString::to_string).collect();
+                self.config_backup = backup;
+                self.use_stored_config(config);
+            }
+            Err(e) => {
+                tracing::warn!("{}. Keeping the current config.", e);
+                self.config_problems = vec![e.to_string()];
+                self.config_backup = None;
+            }
+        }
+    }
+
+    /// Use a new config, passing on the settings the background threads use
+    fn set_config(&mut self, config: Config) {
+        self.config = config;
+        self.cpu_monitor.set_cgroup(&self.config.cgroup_path);
+        self.cpu_monitor.set_interval(self.config.sample_interval());
+        self.metrics_monitor.configure(metric_settings(&self.config));
+        self.command_runner.configure(command_settings(&self.config));
+    }
+
+    /// Use a config read from the store or config.json.  While a schedule's
+    /// profile is applied, it is applied again over the new config — unless
+    /// the stored profile changed, which means the user picked one by hand
+    /// and the schedule no longer overrides it.
+    fn use_stored_config(&mut self, config: Config) {
+        match self.config_before_schedule.take() {
+            Some(before) if before.active_profile == config.active_profile => {
+                let mut scheduled = config.clone();
+                if let Some(ScheduleAction::Profile(profile)) =
+                    self.active_schedule.as_ref().map(|s| &s.action)
+                {
+                    if let Err(e) = scheduled.switch_profile(profile) {
+                        tracing::warn!("{}", e);
+                    }
+                }
+                self.config_before_schedule = Some(config);
+                self.set_config(scheduled);
+            }
+            _ => self.set_config(config),
+        }
+        self.update_schedule();
+    }
+
+    /// Switch to a profile the user picked and save the switch, so the
+    /// settings window and the next start agree (the watcher's reload of it
+    /// is harmless).  This overrides a schedule's profile for the rest of
+    /// the schedule.
+    fn switch_profile(&mut self, name: &str) {
+        let mut config = self.config_before_schedule.clone().unwrap_or_else(|| self.config.clone());
+        match config.switch_profile(name) {
+            Ok(()) => {
+                if let Err(e) = config.save() {
+                    tracing::error!("Failed to save profile switch: {}", e);
+                }
+                self.config_before_schedule = None;
+                self.set_config(config);
+            }
+            Err(e) => tracing::warn!("{}", e),
+        }
+    }
+
+    /// Start or end schedules according to the current local time.  A
+    /// profile schedule switches to its profile when it starts, without
+    /// saving, and back to the stored config when it ends — unless the user
+    /// picked a profile in the meantime.
+    fn update_schedule(&mut self) {
+        let (day, time) = schedule::now();
+        let active = schedule::active_schedule(&self.config.schedules, day, time).cloned();
+        if active == self.active_schedule {
+            return;
+        }
+
+        if let Some(ended) = &self.active_schedule {
+            tracing::info!("Schedule {} ended", ended.name);
+            if let Some(before) = self.config_before_schedule.take() {
+                self.set_config(before);
+            }
+        }
+        if let Some(started) = &active {
+            tracing::info!("Schedule {} started", started.name);
+            if let ScheduleAction::Profile(profile) = &started.action {
+                let mut scheduled = self.config.clone();
+                match scheduled.switch_profile(profile) {
+                    Ok(()) => {
+                        self.config_before_schedule = Some(self.config.clone());
+                        self.set_config(scheduled);
+                    }
+                    Err(e) => tracing::warn!("{}", e),
+                }
+            }
+        }
+        self.active_schedule = active;
+    }
+
+    /// Whether a schedule is keeping the cat asleep right now
+    fn quiet_hours(&self) -> bool {
+        matches!(&self.active_schedule, Some(s) if s.action == ScheduleAction::Sleep)
+    }
+
+    /// Whether the cat is shown asleep: below its threshold, or quiet hours
+    fn is_sleeping(&self) -> bool {
+        self.quiet_hours() || self.sleep_state.is_sleeping()
+    }
+
+    /// Re-read the desktop theme and recolor the sprites if it changed
+    fn reload_theme(&mut self) {
+        let theme_colors = theme::get_cosmic_theme_colors();
+        if theme_colors.foreground != self.accent_color
+            || theme_colors.is_dark != self.theme_is_dark
+        {
+            self.accent_color = theme_colors.foreground;
+            self.theme_is_dark = theme_colors.is_dark;
+            // Re-tint all sprites to match the new accent color
+            self.sprites.update_colors(theme_colors.foreground);
+        }
+    }
+
     /// Generate a short tooltip string like "CPU: 42%" or "CPU: 3200 MHz"
     fn make_tooltip(&self) -> String {
         match self.config.animation_source {
-            AnimationSource::CpuUsage => format!("CPU: {:.0}%", self.smoothed_cpu),
+            AnimationSource::CpuUsage => match &self.cpu_usage.cgroup {
+                Some(cgroup) => format!("{}: {:.0}%", cgroup.path, self.cpu_smoother.value()),
+                None => format!("CPU: {:.0}%", self.cpu_smoother.value()),
+            },
             AnimationSource::Frequency => {
-                format!("CPU: {} MHz", self.cpu_frequency.average_mhz())
+                format!("CPU: {:.0} MHz", self.frequency_smoother.value())
+            }
+            AnimationSource::Temperature => {
+                format!("CPU: {:.1}\u{00b0}C", self.temperature_smoother.value())
+            }
+            AnimationSource::Memory => format!("RAM: {:.0}%", self.memory.percentage()),
+            AnimationSource::Network => {
+                format!("Net: {}", format_rate(self.network.total_bytes_per_sec()))
             }
+            AnimationSource::DiskIo => format!("Disk: {:.0}% busy", self.disk.max_util()),
+            AnimationSource::Gpu => format!("GPU: {:.0}%", self.gpu.max_busy()),
+            AnimationSource::Pressure => {
+                format!("PSI: {:.1}% stalled", self.pressure.worst_avg10())
+            }
+            AnimationSource::LoadAverage => format!(
+                "Load: {:.2} ({:.0}% per CPU)",
+                self.load_average.one,
+                self.load_average.per_cpu_percentage(self.load_average.one)
+            ),
+            AnimationSource::Power => format!("Power: {:.1} W", self.power.watts()),
+            AnimationSource::Mix => {
+                format!("Mix: {:.0}%", self.source_percentage(AnimationSource::Mix))
+            }
+            AnimationSource::Command => match &self.command_result {
+                Some(Ok(output)) => output.tooltip.clone().unwrap_or_else(|| {
+                    let label = output.label.as_deref().unwrap_or("Command");
+                    format!("{}: {}", label, output.value)
+                }),
+                Some(Err(e)) => format!("Command: {}", e),
+                None => "Command: waiting...".to_string(),
+            },
+        }
+    }
+
+    /// The current value of a source on the 0-100% scale that drives the
+    /// animation speed.
+    fn source_percentage(&self, source: AnimationSource) -> f32 {
+        match source {
+            AnimationSource::CpuUsage => self.cpu_smoother.value(),
+            AnimationSource::Frequency => self.frequency_pct_smoother.value(),
             AnimationSource::Temperature => {
-                format!("CPU: {:.1}\u{00b0}C", self.cpu_temperature.max_temp())
+                self.cpu_temperature.percentage_of(self.temperature_smoother.value())
+            }
+            AnimationSource::Memory => self.memory.percentage(),
+            AnimationSource::Network => {
+                self.network.percentage(self.config.network_full_speed_mbps)
             }
+            AnimationSource::DiskIo => self.disk.max_util(),
+            AnimationSource::Gpu => self.gpu.max_busy(),
+            AnimationSource::Pressure => self.pressure.worst_avg10(),
+            AnimationSource::LoadAverage => self.load_average.percentage(),
+            AnimationSource::Power => self.power.percentage(self.config.power_full_watts),
+            AnimationSource::Mix => self.config.mix_metric(&self.mix_contributions()),
+            AnimationSource::Command => match &self.command_result {
+                Some(Ok(output)) => {
+                    (output.value / self.config.command_full_speed * 100.0).clamp(0.0, 100.0)
+                }
+                _ => 0.0,
+            },
+        }
+    }
+
+    /// The current value of a source in the same units as its sleep
+    /// threshold — MHz for frequency, degrees for temperature, KB/s for
+    /// network, watts for power, the raw output for a command, and the
+    /// 0-100% metric for everything else.
+    fn threshold_value(&self, source: AnimationSource) -> f32 {
+        match source {
+            // A failed or pending command always sleeps, whatever the
+            // threshold — the popup says why
+            AnimationSource::Command => match &self.command_result {
+                Some(Ok(output)) => output.value,
+                _ => f32::NEG_INFINITY,
+            },
+            AnimationSource::Frequency => self.frequency_smoother.value(),
+            AnimationSource::Temperature => self.temperature_smoother.value(),
+            AnimationSource::Network => (self.network.total_bytes_per_sec() / 1000.0) as f32,
+            AnimationSource::Power => self.power.watts(),
+            AnimationSource::CpuUsage
+            | AnimationSource::Memory
+            | AnimationSource::DiskIo
+            | AnimationSource::Gpu
+            | AnimationSource::Pressure
+            | AnimationSource::LoadAverage
+            | AnimationSource::Mix => self.source_percentage(source),
         }
     }
 
+    /// What each metric in the weighted mix currently contributes
+    fn mix_contributions(&self) -> Vec<MixContribution> {
+        self.config
+            .mix_contributions(|source| self.source_percentage(source))
+    }
+
     /// Build the popup content: title, scrollable stats area, status text,
     /// and a "Settings" button.  The stats shown depend on the configured
     /// animation source (CPU usage / frequency / temperature).
@@ -661,6 +1122,18 @@ impl RunkatApplet {
             AnimationSource::CpuUsage => "CPU Usage",
             AnimationSource::Frequency => "CPU Frequency",
             AnimationSource::Temperature => "CPU Temperature",
+            AnimationSource::Memory => "Memory Usage",
+            AnimationSource::Network => "Network Throughput",
+            AnimationSource::DiskIo => "Disk I/O",
+            AnimationSource::Gpu => "GPU Usage",
+            AnimationSource::Pressure => "Pressure Stall",
+            AnimationSource::LoadAverage => "Load Average",
+            AnimationSource::Power => "Power Draw",
+            AnimationSource::Mix => match self.config.mix_mode {
+                MixMode::Weighted => "Weighted Mix",
+                MixMode::Max => "Mix (Maximum)",
+            },
+            AnimationSource::Command => "Custom Command",
         };
 
         let title_row = row![
@@ -670,24 +1143,60 @@ impl RunkatApplet {
         .spacing(8)
         .align_y(Alignment::Center);
 
+        // Profile switcher: one button per profile, the active one highlighted
+        let mut profile_row = row![text::caption("Profile:")].spacing(4).align_y(Alignment::Center);
+        for profile in &self.config.profiles {
+            let button = if profile.name == self.config.active_profile {
+                widget::button::suggested(profile.name.as_str())
+            } else {
+                widget::button::standard(profile.name.as_str())
+            };
+            profile_row =
+                profile_row.push(button.on_press(Message::SwitchProfile(profile.name.clone())));
+        }
+
         // Build the per-core statistics area (different layout per source)
         let stats_content: Element<'_, Message> = match self.config.animation_source {
             AnimationSource::CpuUsage => {
-                // Show total CPU usage + one row per core
+                // Show total CPU usage + one row per core, each bar stacked
+                // by where the time went (user, system, iowait, ...)
                 let overall = self.cpu_usage.aggregate;
                 let overall_row = row![
                     text::caption("Total:").width(Length::Fixed(80.0)),
-                    self.progress_bar(overall, 100.0, false),
+                    self.stacked_bar(&self.cpu_usage.breakdown),
                     text::caption(format!("{:5.1}%", overall)).width(Length::Fixed(55.0)),
                 ]
                 .spacing(8)
                 .align_y(Alignment::Center);
 
+                // Legend: color swatch + aggregate share, four per row
+                let mut legend = column![].spacing(2);
+                for chunk in self.cpu_usage.breakdown.parts().chunks(4) {
+                    let mut legend_row = row![].spacing(8).align_y(Alignment::Center);
+                    for &(name, share) in chunk {
+                        let color = breakdown_color(name, self.accent_color);
+                        let swatch = container(Space::new(Length::Fixed(8.0), Length::Fixed(8.0)))
+                            .style(move |_: &cosmic::Theme| container::Style {
+                                background: Some(cosmic::iced::Background::Color(color)),
+                                ..Default::default()
+                            });
+                        legend_row = legend_row.push(
+                            row![swatch, text::caption(format!("{} {:.0}%", name, share))]
+                                .spacing(3)
+                                .align_y(Alignment::Center)
+                                .width(Length::Fixed(74.0)),
+                        );
+                    }
+                    legend = legend.push(legend_row);
+                }
+
                 let mut core_column = column![].spacing(2);
                 for (i, &pct) in self.cpu_usage.per_core.iter().enumerate() {
+                    let breakdown =
+                        self.cpu_usage.per_core_breakdown.get(i).copied().unwrap_or_default();
                     let core_row = row![
                         text::caption(format!("CPU{}:", i)).width(Length::Fixed(80.0)),
-                        self.progress_bar(pct, 100.0, false),
+                        self.stacked_bar(&breakdown),
                         text::caption(format!("{:5.1}%", pct)).width(Length::Fixed(55.0)),
                     ]
                     .spacing(8)
@@ -695,7 +1204,33 @@ impl RunkatApplet {
                     core_column = core_column.push(core_row);
                 }
 
-                column![overall_row, core_column].spacing(4).into()
+                // The cgroup the cat is following, if one is configured
+                let mut cpu_column = column![].spacing(4);
+                if let Some(cgroup) = &self.cpu_usage.cgroup {
+                    let cgroup_section: Element<'_, Message> = match cgroup.percent {
+                        Some(pct) => column![
+                            text::caption(format!("Cgroup {}", cgroup.path)),
+                            row![
+                                text::caption("Usage:").width(Length::Fixed(80.0)),
+                                self.progress_bar(pct, 100.0, false),
+                                text::caption(format!("{:.2} CPUs", cgroup.cpus))
+                                    .width(Length::Fixed(55.0)),
+                            ]
+                            .spacing(8)
+                            .align_y(Alignment::Center),
+                        ]
+                        .spacing(2)
+                        .into(),
+                        None => text::caption(format!(
+                            "Cannot read cpu.stat for cgroup {}",
+                            cgroup.path
+                        ))
+                        .into(),
+                    };
+                    cpu_column = cpu_column.push(cgroup_section);
+                }
+
+                cpu_column.push(overall_row).push(legend).push(core_column).into()
             }
 
             AnimationSource::Frequency => {
@@ -770,37 +1305,403 @@ impl RunkatApplet {
 
                 temp_column.into()
             }
-        };
 
-        // Fun status text describing the cat's current state
-        let status_text = match self.config.animation_source {
-            AnimationSource::CpuUsage => {
-                if self.cpu_usage.aggregate < self.config.sleep_threshold_cpu {
-                    "Cat is sleeping..."
-                } else {
-                    "Cat is running!"
+            AnimationSource::Memory => {
+                // Show used RAM, then what the rest of it is doing
+                let mem = &self.memory;
+                let total = mem.total_kb as f32;
+
+                let mut mem_column = column![].spacing(2);
+                for (label, used_kb, max_kb) in [
+                    ("Used:", mem.used_kb(), total),
+                    ("Cached:", mem.cached_kb, total),
+                    ("Buffers:", mem.buffers_kb, total),
+                    ("Swap:", mem.swap_used_kb(), mem.swap_total_kb as f32),
+                ] {
+                    let mem_row = row![
+                        text::caption(label).width(Length::Fixed(80.0)),
+                        self.progress_bar(used_kb as f32, max_kb, false),
+                        text::caption(format!("{:.1} GiB", kb_to_gib(used_kb)))
+                            .width(Length::Fixed(80.0)),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center);
+                    mem_column = mem_column.push(mem_row);
                 }
+
+                mem_column.into()
             }
-            AnimationSource::Frequency => {
-                let avg_mhz = self.cpu_frequency.average_mhz() as f32;
-                if avg_mhz < self.config.sleep_threshold_freq {
-                    "Cat is idle..."
-                } else {
-                    "Cat is boosting!"
+
+            AnimationSource::Network => {
+                // Show combined throughput + receive/transmit rows per interface
+                let full_speed = mbps_to_bytes_per_sec(self.config.network_full_speed_mbps) as f32;
+                let total = self.network.total_bytes_per_sec();
+
+                let total_row = row![
+                    text::caption("Total:").width(Length::Fixed(80.0)),
+                    self.progress_bar(total as f32, full_speed, false),
+                    text::caption(format_rate(total)).width(Length::Fixed(80.0)),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center);
+
+                let mut iface_column = column![].spacing(2);
+                for iface in &self.network.interfaces {
+                    for (direction, rate) in [
+                        ("rx", iface.rx_bytes_per_sec),
+                        ("tx", iface.tx_bytes_per_sec),
+                    ] {
+                        let iface_row = row![
+                            text::caption(format!("{} {}:", iface.name, direction))
+                                .width(Length::Fixed(80.0)),
+                            self.progress_bar(rate as f32, full_speed, false),
+                            text::caption(format_rate(rate)).width(Length::Fixed(80.0)),
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center);
+                        iface_column = iface_column.push(iface_row);
+                    }
                 }
+
+                column![total_row, iface_column].spacing(4).into()
             }
-            AnimationSource::Temperature => {
-                let max_temp = self.cpu_temperature.max_temp();
-                if max_temp < self.config.sleep_threshold_temp {
-                    "Cat is cool..."
-                } else if max_temp > TEMP_HOT_THRESHOLD {
-                    "Cat is HOT!"
-                } else {
-                    "Cat is warm..."
+
+            AnimationSource::DiskIo => {
+                // Show the busiest disk, then utilisation + read/write per disk
+                let busiest = self.disk.max_util();
+                let busiest_row = row![
+                    text::caption("Busiest:").width(Length::Fixed(80.0)),
+                    self.progress_bar(busiest, 100.0, false),
+                    text::caption(format!("{:5.1}%", busiest)).width(Length::Fixed(80.0)),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center);
+
+                let mut disk_column = column![].spacing(2);
+                for disk in &self.disk.disks {
+                    let util_row = row![
+                        text::caption(format!("{}:", disk.name)).width(Length::Fixed(80.0)),
+                        self.progress_bar(disk.util_percent, 100.0, false),
+                        text::caption(format!("{:5.1}%", disk.util_percent))
+                            .width(Length::Fixed(80.0)),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center);
+                    disk_column = disk_column.push(util_row);
+
+                    for (direction, rate) in [
+                        ("  read", disk.read_bytes_per_sec),
+                        ("  write", disk.write_bytes_per_sec),
+                    ] {
+                        let rate_row = row![
+                            text::caption(direction).width(Length::Fixed(80.0)),
+                            self.progress_bar(rate as f32, DISK_BAR_FULL_BYTES_PER_SEC, true),
+                            text::caption(format_rate(rate)).width(Length::Fixed(80.0)),
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center);
+                        disk_column = disk_column.push(rate_row);
+                    }
+                }
+
+                column![busiest_row, disk_column].spacing(4).into()
+            }
+
+            AnimationSource::Gpu => {
+                // One section per card: load, VRAM and temperature, each
+                // only if the driver exposes it
+                let mut gpu_column = column![].spacing(8);
+                for card in &self.gpu.cards {
+                    let heading = if card.driver.is_empty() {
+                        card.name.clone()
+                    } else {
+                        format!("{} ({})", card.name, card.driver)
+                    };
+                    let mut card_column = column![text::caption(heading)].spacing(2);
+
+                    if let Some(busy) = card.busy_percent {
+                        let busy_row = row![
+                            text::caption("Busy:").width(Length::Fixed(80.0)),
+                            self.progress_bar(busy, 100.0, false),
+                            text::caption(format!("{:5.1}%", busy)).width(Length::Fixed(80.0)),
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center);
+                        card_column = card_column.push(busy_row);
+                    }
+
+                    if let (Some(used), Some(total)) = (card.vram_used, card.vram_total) {
+                        let vram_row = row![
+                            text::caption("VRAM:").width(Length::Fixed(80.0)),
+                            self.progress_bar(used as f32, total as f32, false),
+                            text::caption(format!("{:.1} GiB", kb_to_gib(used / 1024)))
+                                .width(Length::Fixed(80.0)),
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center);
+                        card_column = card_column.push(vram_row);
+                    }
+
+                    if let Some(temp) = card.temperature {
+                        let temp_row = row![
+                            text::caption("Temp:").width(Length::Fixed(80.0)),
+                            self.progress_bar(temp, 100.0, false),
+                            text::caption(format!("{:.1}\u{00b0}C", temp))
+                                .width(Length::Fixed(80.0)),
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center);
+                        card_column = card_column.push(temp_row);
+                    }
+
+                    gpu_column = gpu_column.push(card_column);
+                }
+
+                if self.gpu.cards.is_empty() {
+                    gpu_column = gpu_column.push(text::caption("No GPU readings available"));
+                }
+
+                gpu_column.into()
+            }
+
+            AnimationSource::Pressure => {
+                // One section per resource with its 10s / 60s / 300s averages
+                let mut psi_column = column![].spacing(8);
+                for (resource, stat) in [
+                    ("CPU", self.pressure.cpu),
+                    ("Memory", self.pressure.memory),
+                    ("I/O", self.pressure.io),
+                ] {
+                    let Some(stat) = stat else {
+                        continue;
+                    };
+
+                    let mut resource_column = column![text::caption(resource)].spacing(2);
+                    for (window, value) in [
+                        ("  10s:", stat.avg10),
+                        ("  60s:", stat.avg60),
+                        ("  300s:", stat.avg300),
+                    ] {
+                        let avg_row = row![
+                            text::caption(window).width(Length::Fixed(80.0)),
+                            self.progress_bar(value, 100.0, false),
+                            text::caption(format!("{:5.2}%", value)).width(Length::Fixed(80.0)),
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center);
+                        resource_column = resource_column.push(avg_row);
+                    }
+                    psi_column = psi_column.push(resource_column);
+                }
+
+                if !self.pressure.is_available() {
+                    psi_column = psi_column
+                        .push(text::caption("PSI not available (kernel needs CONFIG_PSI)"));
+                }
+
+                psi_column.into()
+            }
+
+            AnimationSource::LoadAverage => {
+                // Show each average with its bar scaled per CPU, then tasks
+                let load = &self.load_average;
+                let mut load_column = column![].spacing(2);
+                for (window, value) in [
+                    ("1 min:", load.one),
+                    ("5 min:", load.five),
+                    ("15 min:", load.fifteen),
+                ] {
+                    let load_row = row![
+                        text::caption(window).width(Length::Fixed(80.0)),
+                        self.progress_bar(load.per_cpu_percentage(value), 100.0, false),
+                        text::caption(format!("{:.2}", value)).width(Length::Fixed(80.0)),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center);
+                    load_column = load_column.push(load_row);
+                }
+
+                let tasks_row = row![
+                    text::caption("Tasks:").width(Length::Fixed(80.0)),
+                    text::caption(format!(
+                        "{} runnable of {} on {} CPUs",
+                        load.runnable, load.total_tasks, load.online_cpus
+                    )),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center);
+
+                column![load_column, tasks_row].spacing(4).into()
+            }
+
+            AnimationSource::Power => {
+                // RAPL domains scaled to the full-speed wattage, then one
+                // section per battery
+                let full_watts = self.config.power_full_watts;
+                let mut power_column = column![].spacing(2);
+                for domain in &self.power.domains {
+                    let label = if domain.is_subzone() {
+                        format!("  {}:", domain.display_name())
+                    } else {
+                        format!("{}:", domain.display_name())
+                    };
+                    let domain_row = row![
+                        text::caption(label).width(Length::Fixed(80.0)),
+                        self.progress_bar(domain.watts, full_watts, false),
+                        text::caption(format!("{:.1} W", domain.watts))
+                            .width(Length::Fixed(80.0)),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center);
+                    power_column = power_column.push(domain_row);
+                }
+
+                if !self.power.has_rapl() {
+                    power_column = power_column
+                        .push(text::caption("RAPL not readable (energy_uj is root-only)"));
+                }
+
+                for battery in &self.power.batteries {
+                    let charge = battery.capacity.unwrap_or(0.0);
+                    let charge_row = row![
+                        text::caption(format!("{}:", battery.name)).width(Length::Fixed(80.0)),
+                        self.progress_bar(charge, 100.0, false),
+                        text::caption(format!("{:.0}% {}", charge, battery.status))
+                            .width(Length::Fixed(80.0)),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center);
+                    power_column = power_column.push(charge_row);
+
+                    if let Some(watts) = battery.watts {
+                        let rate_label = if battery.is_discharging() {
+                            "  Discharge:"
+                        } else {
+                            "  Charge:"
+                        };
+                        let rate_row = row![
+                            text::caption(rate_label).width(Length::Fixed(80.0)),
+                            self.progress_bar(watts, full_watts, false),
+                            text::caption(format!("{:.1} W", watts)).width(Length::Fixed(80.0)),
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center);
+                        power_column = power_column.push(rate_row);
+                    }
+                }
+
+                power_column.into()
+            }
+
+            AnimationSource::Mix => {
+                // Show the combined value, then each metric's raw value as a
+                // bar and what it contributes to the mix as text
+                let mixed = self.source_percentage(AnimationSource::Mix);
+                let mix_row = row![
+                    text::caption("Combined:").width(Length::Fixed(110.0)),
+                    self.progress_bar(mixed, 100.0, false),
+                    text::caption(format!("{:5.1}%", mixed)).width(Length::Fixed(50.0)),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center);
+
+                let mut source_column = column![].spacing(2);
+                for c in self.mix_contributions() {
+                    let source_row = row![
+                        text::caption(format!("{} \u{00d7}{:.1}", c.source.name(), c.weight))
+                            .width(Length::Fixed(110.0)),
+                        self.progress_bar(c.value, 100.0, false),
+                        text::caption(format!("{:5.1}%", c.contribution))
+                            .width(Length::Fixed(50.0)),
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center);
+                    source_column = source_column.push(source_row);
+                }
+
+                column![mix_row, source_column].spacing(4).into()
+            }
+
+            AnimationSource::Command => {
+                // Show the value scaled against the full-speed value, any
+                // tooltip text it supplied, or what went wrong
+                match &self.command_result {
+                    _ if self.config.command_line.trim().is_empty() => {
+                        text::caption("No command configured — set one in Settings").into()
+                    }
+                    None => text::caption("Waiting for the command to finish...").into(),
+                    Some(Err(e)) => column![
+                        text::caption("Command failed:"),
+                        text::caption(e.clone()),
+                    ]
+                    .spacing(2)
+                    .into(),
+                    Some(Ok(output)) => {
+                        let label = output.label.as_deref().unwrap_or("Value");
+                        let value_row = row![
+                            text::caption(format!("{}:", label)).width(Length::Fixed(80.0)),
+                            self.progress_bar(
+                                output.value,
+                                self.config.command_full_speed,
+                                false
+                            ),
+                            text::caption(format!("{}", output.value))
+                                .width(Length::Fixed(80.0)),
+                        ]
+                        .spacing(8)
+                        .align_y(Alignment::Center);
+
+                        let mut command_column = column![value_row].spacing(4);
+                        if let Some(tooltip) = &output.tooltip {
+                            command_column = command_column.push(text::caption(tooltip.clone()));
+                        }
+                        command_column.into()
+                    }
                 }
             }
         };
 
+        // Fun status text describing the cat's current state.  Asleep or
+        // awake comes from the same state machine that drives the sprite.
+        let sleeping = self.is_sleeping();
+        let status_text = match self.config.animation_source {
+            _ if self.quiet_hours() => "Cat is resting (quiet hours)...",
+            AnimationSource::Command => match &self.command_result {
+                Some(Err(_)) => "Cat is confused...",
+                None => "Cat is waiting...",
+                Some(Ok(_)) if sleeping => "Cat is sleeping...",
+                Some(Ok(_)) => "Cat is running!",
+            },
+            AnimationSource::Frequency if sleeping => "Cat is idle...",
+            AnimationSource::Temperature if sleeping => "Cat is cool...",
+            _ if sleeping => "Cat is sleeping...",
+            AnimationSource::Frequency => "Cat is boosting!",
+            AnimationSource::Temperature
+                if self.temperature_smoother.value() > TEMP_HOT_THRESHOLD =>
+            {
+                "Cat is HOT!"
+            }
+            AnimationSource::Temperature => "Cat is warm...",
+            AnimationSource::Network
+                if self.network.percentage(self.config.network_full_speed_mbps) > 90.0 =>
+            {
+                "Link is saturated!"
+            }
+            AnimationSource::DiskIo if self.disk.max_util() > 90.0 => "Disk is thrashing!",
+            AnimationSource::Pressure if self.pressure.worst_avg10() > 25.0 => {
+                "System is stalling!"
+            }
+            AnimationSource::LoadAverage
+                if self.load_average.per_cpu_percentage(self.load_average.one) > 100.0 =>
+            {
+                "Tasks are queueing!"
+            }
+            AnimationSource::Power if self.power.watts() > self.config.power_full_watts => {
+                "Cat is burning power!"
+            }
+            _ => "Cat is running!",
+        };
+
         let bottom_row = row![
             text::caption(status_text),
             horizontal_space(),
@@ -828,10 +1729,33 @@ impl RunkatApplet {
             )
         };
 
-        // Assemble: title | divider | stats | divider | status + settings button
-        column![title_row, divider(), scrollable_stats, divider(), bottom_row,]
-            .spacing(8)
-            .padding(12)
+        // Assemble: title | [profiles] | [active schedule] | divider | stats | divider |
+        // [config problems | divider] | status + settings button
+        let mut content = column![title_row];
+        if !self.config.profiles.is_empty() {
+            content = content.push(profile_row);
+        }
+        if let Some(active) = &self.active_schedule {
+            content = content.push(text::caption(format!(
+                "Schedule: {} (until {})",
+                active.name, active.end
+            )));
+        }
+        content = content.push(divider()).push(scrollable_stats).push(divider());
+        if !self.config_problems.is_empty() {
+            let mut problems = column![text::body("Problems in the config:")].spacing(2);
+            for problem in &self.config_problems {
+                problems = problems.push(text::caption(problem.clone()));
+            }
+            if let Some(backup) = &self.config_backup {
+                problems = problems.push(text::caption(format!(
+                    "A copy of the file was kept as {}",
+                    backup.display()
+                )));
+            }
+            content = content.push(problems).push(divider());
+        }
+        content.push(bottom_row).spacing(8).padding(12)
     }
 
     /// Create a colored progress bar widget.
@@ -906,6 +1830,134 @@ impl RunkatApplet {
             })
             .into()
     }
+
+    /// Draw a bar split into colored segments showing where a CPU's time
+    /// went (see `CpuBreakdown::parts()` and `breakdown_color()`).  The
+    /// filled length is `CpuBreakdown::busy()`, the same figure printed beside it.
+    fn stacked_bar(&self, breakdown: &CpuBreakdown) -> Element<'_, Message> {
+        use cosmic::iced::widget::{container, row, Space};
+        use cosmic::iced::Color;
+
+        // One segment per non-empty share, left to right in `parts()` order
+        let mut segments = row![].spacing(0);
+        let mut remaining = BAR_WIDTH;
+        for (name, share) in breakdown.parts() {
+            let width = (share / 100.0 * BAR_WIDTH).clamp(0.0, remaining);
+            if width < 0.5 {
+                continue;
+            }
+            remaining -= width;
+            let color = breakdown_color(name, self.accent_color);
+            segments = segments.push(
+                container(Space::new(Length::Fixed(width), Length::Fixed(BAR_HEIGHT - 2.0)))
+                    .style(move |_: &cosmic::Theme| container::Style {
+                        background: Some(cosmic::iced::Background::Color(color)),
+                        ..Default::default()
+                    }),
+            );
+        }
+
+        // Same grey track as `progress_bar()`
+        container(segments)
+            .width(Length::Fixed(BAR_WIDTH))
+            .height(Length::Fixed(BAR_HEIGHT))
+            .style(|_: &cosmic::Theme| container::Style {
+                background: Some(cosmic::iced::Background::Color(Color::from_rgba(
+                    0.5, 0.5, 0.5, 0.2,
+                ))),
+                border: cosmic::iced::Border {
+                    radius: 4.0.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .into()
+    }
+}
+
+/// Turn a `watch` channel into a subscription that emits a message each
+/// time a new value is sent, so samples arrive exactly when they exist
+/// instead of being polled on a timer.
+///
+/// `id` keeps the subscription alive across calls to `subscription()`
+/// rather than restarting it each time.
+fn watch_subscription<T>(
+    id: &'static str,
+    rx: tokio::sync::watch::Receiver<T>,
+    to_message: fn(T) -> Message,
+) -> cosmic::iced::Subscription<Message>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    use cosmic::iced::futures::SinkExt;
+
+    cosmic::iced::Subscription::run_with_id(
+        id,
+        cosmic::iced::stream::channel(1, move |mut output| async move {
+            let mut rx = rx;
+            // Ends when the sender (the monitor) is dropped
+            while rx.changed().await.is_ok() {
+                let value = rx.borrow_and_update().clone();
+                if output.send(to_message(value)).await.is_err() {
+                    break;
+                }
+            }
+        }),
+    )
+}
+
+/// Bar color for one share of a CPU time breakdown.  Work is drawn in the
+/// accent color and warm tones; time lost to waiting (iowait, steal) in
+/// muted ones.
+fn breakdown_color(name: &str, accent: (u8, u8, u8)) -> cosmic::iced::Color {
+    use cosmic::iced::Color;
+
+    let (r, g, b) = accent;
+    match name {
+        "user" => Color::from_rgb8(r, g, b),
+        "nice" => Color::from_rgba8(r, g, b, 0.55),
+        "system" => Color::from_rgb8(220, 80, 80),
+        "irq" => Color::from_rgb8(170, 90, 200),
+        "softirq" => Color::from_rgb8(200, 140, 225),
+        "guest" => Color::from_rgb8(80, 180, 120),
+        "steal" => Color::from_rgb8(150, 150, 150),
+        "iowait" => Color::from_rgb8(220, 180, 50),
+        _ => Color::from_rgb8(r, g, b),
+    }
+}
+
+/// What the metrics thread should read: only the metrics that drive the
+/// cat directly or via the mix, at the CPU sample interval.
+fn metric_settings(config: &Config) -> MetricSettings {
+    MetricSettings {
+        memory: config.source_in_use(AnimationSource::Memory),
+        network: config.source_in_use(AnimationSource::Network),
+        disk: config.source_in_use(AnimationSource::DiskIo),
+        gpu: config.source_in_use(AnimationSource::Gpu),
+        pressure: config.source_in_use(AnimationSource::Pressure),
+        load_average: config.source_in_use(AnimationSource::LoadAverage),
+        power: config.source_in_use(AnimationSource::Power),
+        network_include: config.network_include.clone(),
+        network_exclude: config.network_exclude.clone(),
+        disk_exclude: config.disk_exclude.clone(),
+        disk_include_partitions: config.disk_include_partitions,
+        interval: config.sample_interval(),
+    }
+}
+
+/// What the custom command thread should run, or `None` to leave it idle —
+/// it only runs while the command drives the cat directly or via the mix.
+fn command_settings(config: &Config) -> Option<CommandSettings> {
+    let command_line = config.command_line.trim();
+    if !config.source_in_use(AnimationSource::Command) || command_line.is_empty() {
+        return None;
+    }
+
+    Some(CommandSettings {
+        command_line: command_line.to_string(),
+        interval: Duration::from_secs_f32(config.command_interval_secs),
+        timeout: Duration::from_secs_f32(config.command_timeout_secs),
+    })
 }
 
 /// Entry point: launch the COSMIC panel applet.
@@ -0,0 +1,337 @@
+//! Custom command metric module
+//!
+//! Runs a user-supplied shell command periodically and turns its output
+//! into a metric for the cat, so it can follow things RunKat knows nothing
+//! about — a job queue's depth, a build farm's backlog, and so on.
+//!
+//! ## Output format
+//!
+//! The command's stdout must be either a bare number:
+//!
+//! ```text
+//! 42
+//! ```
+//!
+//! or a JSON object with a numeric `value` and optional `label` / `tooltip`
+//! strings:
+//!
+//! ```text
+//! {"value": 42, "label": "Queued jobs", "tooltip": "42 jobs waiting"}
+//! ```
+//!
+//! ## Architecture
+//!
+//! Commands can be slow or hang, so they run on a dedicated **OS thread**
+//! (like `CpuMonitor`) and the latest result is published through a tokio
+//! `watch` channel.  Each run is killed after a timeout.  Failures —
+//! timeouts, non-zero exits, unparseable output — are published as errors
+//! so the popup can show them, rather than quietly reading as zero.
+
+use serde::Deserialize;
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// A successfully parsed command result.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct CommandOutput {
+    /// The number that drives the cat
+    pub value: f32,
+    /// Optional name for the value, shown in the popup (e.g. "Queued jobs")
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Optional text for the panel tooltip
+    #[serde(default)]
+    pub tooltip: Option<String>,
+}
+
+/// Outcome of the most recent run: the output, or a message saying what
+/// went wrong.
+pub type CommandResult = Result<CommandOutput, String>;
+
+/// What to run and how often.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandSettings {
+    /// Shell command line, run with `sh -c`
+    pub command_line: String,
+    /// Time between the end of one run and the start of the next
+    pub interval: Duration,
+    /// How long a run may take before it is killed
+    pub timeout: Duration,
+}
+
+/// Parse a command's stdout into a `CommandOutput`.
+///
+/// Accepts a bare number (a trailing `%` is allowed) or a JSON object with
+/// a `value` field.
+pub fn parse_output(stdout: &str) -> CommandResult {
+    let trimmed = stdout.trim();
+
+    if trimmed.starts_with('{') {
+        return serde_json::from_str::<CommandOutput>(trimmed)
+            .map_err(|e| format!("Invalid JSON output: {}", e));
+    }
+
+    trimmed
+        .trim_end_matches('%')
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .map(|value| CommandOutput { value, ..Default::default() })
+        .ok_or_else(|| format!("Not a number: \"{}\"", truncate(trimmed, 40)))
+}
+
+/// Run a command line with `sh -c`, wait up to `timeout` for it, and parse
+/// its output.
+///
+/// The shell runs in a process group of its own, so a timeout kills
+/// everything it started (e.g. both sides of `curl ... | jq ...`), not just
+/// the shell.
+pub fn run_command(command_line: &str, timeout: Duration) -> CommandResult {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command_line)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| format!("Failed to start: {}", e))?;
+
+    // Drain stdout/stderr on their own threads so a chatty command can't
+    // block on a full pipe while we wait for it to exit
+    let stdout = spawn_reader(child.stdout.take());
+    let stderr = spawn_reader(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                kill_process_group(&child);
+                let _ = child.wait(); // reap the zombie
+                return Err(format!("Timed out after {:.1}s", timeout.as_secs_f32()));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(20)),
+            Err(e) => return Err(format!("Failed to wait for command: {}", e)),
+        }
+    };
+
+    // The pipes close when the command exits — unless it left a background
+    // process holding them, so don't wait forever for the readers either
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    let output = stdout.recv_timeout(remaining).unwrap_or_default();
+
+    if !status.success() {
+        let stderr = stderr.recv_timeout(Duration::from_millis(100)).unwrap_or_default();
+        let detail = stderr.lines().next().unwrap_or_default();
+        return Err(match status.code() {
+            Some(code) if detail.is_empty() => format!("Exited with status {}", code),
+            Some(code) => format!("Exited with status {}: {}", code, truncate(detail, 60)),
+            None => "Killed by a signal".to_string(),
+        });
+    }
+
+    parse_output(&output)
+}
+
+/// Kill a child started with `process_group(0)` and everything else in its
+/// group.
+fn kill_process_group(child: &Child) {
+    // The child leads its own group, so the group ID is its PID
+    let Ok(pgid) = libc::pid_t::try_from(child.id()) else {
+        return;
+    };
+    // SAFETY: kill() only sends a signal; a negative PID names the group
+    if unsafe { libc::kill(-pgid, libc::SIGKILL) } != 0 {
+        tracing::warn!("Failed to kill timed-out command: {}", std::io::Error::last_os_error());
+    }
+}
+
+/// Read a child pipe to the end on a background thread.
+fn spawn_reader(pipe: Option<impl Read + Send + 'static>) -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    if let Some(mut pipe) = pipe {
+        thread::spawn(move || {
+            let mut buf = String::new();
+            let _ = pipe.read_to_string(&mut buf);
+            let _ = tx.send(buf);
+        });
+    }
+    rx
+}
+
+/// Shorten a string to at most `max` characters for display.
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        format!("{}...", s.chars().take(max).collect::<String>())
+    }
+}
+
+/// Runs the configured command on a background thread and provides the
+/// latest result via a `watch` channel.
+///
+/// Nothing runs until `configure()` is given some settings; passing `None`
+/// pauses it again (e.g. when the user switches to a different source).
+/// `subscribe()` to be notified of each result.  The thread is stopped
+/// when the runner is dropped.
+pub struct CommandRunner {
+    /// Latest result, `None` until the first run finishes — cloned for
+    /// each subscriber
+    rx: watch::Receiver<Option<CommandResult>>,
+    /// New settings for the thread, which waits on them between runs.
+    /// Dropping it stops the thread.
+    settings_tx: mpsc::Sender<Option<CommandSettings>>,
+    /// The settings last sent, so unchanged ones aren't sent again
+    settings: Mutex<Option<CommandSettings>>,
+}
+
+impl CommandRunner {
+    /// Create a runner and start its (initially idle) background thread.
+    pub fn new() -> Self {
+        let (tx, rx) = watch::channel(None);
+        let (settings_tx, settings_rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut settings: Option<CommandSettings> = None;
+            loop {
+                if let Some(settings) = &settings {
+                    let result = run_command(&settings.command_line, settings.timeout);
+                    if let Err(e) = &result {
+                        tracing::warn!("Custom command failed: {}", e);
+                    }
+                    if tx.send(Some(result)).is_err() {
+                        break; // runner dropped
+                    }
+                }
+
+                // Wait for the next run, waking early if the settings change
+                // (and only for that while paused)
+                let next = match &settings {
+                    Some(settings) => settings_rx.recv_timeout(settings.interval),
+                    None => settings_rx.recv().map_err(RecvTimeoutError::from),
+                };
+                match next {
+                    Ok(new) => settings = new,
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break, // runner dropped
+                }
+            }
+        });
+
+        Self { rx, settings_tx, settings: Mutex::new(None) }
+    }
+
+    /// Change what the runner executes.  Does nothing if the settings are
+    /// unchanged, so it's cheap to call on every config reload.
+    pub fn configure(&self, settings: Option<CommandSettings>) {
+        let mut current = self.settings.lock().unwrap_or_else(PoisonError::into_inner);
+        if *current == settings {
+            return;
+        }
+        *current = settings.clone();
+        let _ = self.settings_tx.send(settings);
+    }
+
+    /// Get a receiver that is notified each time the background thread
+    /// finishes a run.  This never blocks.
+    pub fn subscribe(&self) -> watch::Receiver<Option<CommandResult>> {
+        self.rx.clone()
+    }
+}
+
+impl Default for CommandRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_output("42\n").unwrap().value, 42.0);
+        assert_eq!(parse_output("  12.5% ").unwrap().value, 12.5);
+        assert!(parse_output("").is_err());
+        assert!(parse_output("lots").is_err());
+        assert!(parse_output("NaN").is_err());
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let output =
+            parse_output(r#"{"value": 7, "label": "Queued jobs", "tooltip": "7 waiting"}"#)
+                .unwrap();
+        assert_eq!(output.value, 7.0);
+        assert_eq!(output.label.as_deref(), Some("Queued jobs"));
+        assert_eq!(output.tooltip.as_deref(), Some("7 waiting"));
+
+        // `value` is required
+        assert!(parse_output(r#"{"label": "x"}"#).is_err());
+    }
+
+    #[test]
+    fn test_run_command() {
+        let output = run_command("echo 3", Duration::from_secs(5)).unwrap();
+        assert_eq!(output.value, 3.0);
+    }
+
+    #[test]
+    fn test_run_command_failure() {
+        let err = run_command("echo oops >&2; exit 3", Duration::from_secs(5)).unwrap_err();
+        assert_eq!(err, "Exited with status 3: oops");
+    }
+
+    #[test]
+    fn test_runner_idle_until_configured() {
+        let runner = CommandRunner::new();
+        let mut rx = runner.subscribe();
+        thread::sleep(Duration::from_millis(200));
+        assert!(!rx.has_changed().unwrap());
+
+        runner.configure(Some(CommandSettings {
+            command_line: "echo 4".to_string(),
+            interval: Duration::from_secs(60),
+            timeout: Duration::from_secs(5),
+        }));
+        thread::sleep(Duration::from_millis(500));
+        // The first run starts straight away
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(rx.borrow_and_update().clone().unwrap().unwrap().value, 4.0);
+    }
+
+    #[test]
+    fn test_run_command_timeout() {
+        let err = run_command("sleep 5", Duration::from_millis(200)).unwrap_err();
+        assert!(err.starts_with("Timed out"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_run_command_timeout_kills_children() {
+        let pid_file =
+            std::env::temp_dir().join(format!("cosmic-runkat-command-{}", std::process::id()));
+        let command_line = format!("sleep 30 & echo $! > {}; wait", pid_file.display());
+        assert!(run_command(&command_line, Duration::from_millis(300)).is_err());
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let _ = std::fs::remove_file(&pid_file);
+        thread::sleep(Duration::from_millis(200));
+        // Gone, or a zombie waiting to be reaped
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim()));
+        assert!(stat.map_or(true, |stat| stat.contains(") Z ")), "sleep is still running");
+    }
+}
@@ -1,22 +1,54 @@
 //! Configuration management for cosmic-runkat
 //!
 //! Handles loading, saving, and validating user preferences.  The config
-//! is stored as JSON on disk and automatically reloaded every 500ms by the
-//! applet so that changes from the settings window take effect immediately.
+//! is kept in `cosmic-config`, one entry per field, like other COSMIC
+//! applets; the applet subscribes to its change notifications so that
+//! changes from the settings window (or any tool that edits the entries)
+//! take effect immediately.
 //!
-//! ## Config file location
+//! ## Config location
 //!
-//! `~/.config/cosmic-runkat/config.json` (standard XDG config directory)
+//! `~/.config/cosmic/io.github.reality2_roycdavies.cosmic-runkat/v2/`, one
+//! file per field.
+//!
+//! Older versions used a single JSON file,
+//! `~/.config/cosmic-runkat/config.json`.  The first time the store is used
+//! that file is imported into it and renamed to `config.json.imported`.  If
+//! the store can't be opened at all the JSON file is still used instead,
+//! reloaded by the applet whenever it changes (see `watcher.rs`; if the
+//! file can't be watched it is polled every `config_check_interval_ms`).
+//!
+//! ## Versions and migrations
+//!
+//! The JSON file carries a `version` number.  Added fields only need a
+//! serde default, but renamed or restructured ones get a step in
+//! `MIGRATIONS`, which rewrites the raw JSON from one version to the next
+//! before it is parsed.  Files written before versioning count as
+//! version 1.  The store is versioned by its directory instead.
+//!
+//! Saving the JSON file writes a temp file next to the config and renames
+//! it over the old one, so a reader never sees a half-written file;
+//! cosmic-config does the same for each of its entries.
 
 use crate::constants::*;
+use crate::error::RunkatError;
+use crate::schedule::{Schedule, ScheduleAction};
+use crate::smoothing::Filter;
+use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
 
 /// What system metric drives the cat animation speed.
 ///
 /// This enum is stored in the config file as a kebab-case string
-/// (e.g. `"cpu-usage"`, `"frequency"`, `"temperature"`).
+/// (e.g. `"cpu-usage"`, `"frequency"`, `"temperature"`, `"memory"`,
+/// `"network"`, `"disk-io"`, `"gpu"`, `"pressure"`, `"load-average"`,
+/// `"power"`, `"mix"`, `"command"`).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum AnimationSource {
@@ -27,6 +59,27 @@ pub enum AnimationSource {
     Frequency,
     /// Cat speed reflects CPU temperature
     Temperature,
+    /// Cat speed reflects RAM usage (used vs. available)
+    Memory,
+    /// Cat speed reflects combined network receive + transmit throughput
+    Network,
+    /// Cat speed reflects utilisation of the busiest block device
+    DiskIo,
+    /// Cat speed reflects load of the busiest GPU
+    Gpu,
+    /// Cat speed reflects the worst Pressure Stall Information figure
+    /// (CPU, memory or I/O)
+    Pressure,
+    /// Cat speed reflects the 1-minute load average per online CPU
+    LoadAverage,
+    /// Cat speed reflects power draw — RAPL package watts, or the battery
+    /// discharge rate where RAPL isn't readable
+    Power,
+    /// Cat speed reflects several of the above, combined according to
+    /// `Config::mix_mode` and `Config::mix_weights`
+    Mix,
+    /// Cat speed reflects the number printed by `Config::command_line`
+    Command,
 }
 
 impl AnimationSource {
@@ -35,6 +88,15 @@ impl AnimationSource {
         AnimationSource::CpuUsage,
         AnimationSource::Frequency,
         AnimationSource::Temperature,
+        AnimationSource::Memory,
+        AnimationSource::Network,
+        AnimationSource::DiskIo,
+        AnimationSource::Gpu,
+        AnimationSource::Pressure,
+        AnimationSource::LoadAverage,
+        AnimationSource::Power,
+        AnimationSource::Mix,
+        AnimationSource::Command,
     ];
 
     /// Human-readable names corresponding to `ALL` — shown in the settings UI
@@ -42,21 +104,348 @@ impl AnimationSource {
         "CPU Usage",
         "CPU Frequency",
         "CPU Temperature",
+        "Memory Usage",
+        "Network",
+        "Disk I/O",
+        "GPU Usage",
+        "Pressure (PSI)",
+        "Load Average",
+        "Power Draw",
+        "Weighted Mix",
+        "Custom Command",
+    ];
+
+    /// Human-readable name of this source (the matching `NAMES` entry)
+    pub fn name(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .position(|&s| s == self)
+            .map(|i| Self::NAMES[i])
+            .unwrap_or_default()
+    }
+}
+
+/// How the metrics in a weighted mix are combined into one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum MixMode {
+    /// Weighted average: each metric counts in proportion to its weight
+    Weighted,
+    /// The largest weighted metric wins — "busy OR hot" (default)
+    #[default]
+    Max,
+}
+
+impl MixMode {
+    /// All variants in display order — used for dropdown menus
+    pub const ALL: &'static [MixMode] = &[MixMode::Weighted, MixMode::Max];
+
+    /// Human-readable names corresponding to `ALL` — shown in the settings UI
+    pub const NAMES: &'static [&'static str] = &["Weighted Average", "Maximum"];
+}
+
+/// How a noisy metric is smoothed before it drives the cat (see the
+/// `smoothing` module).
+///
+/// Stored in the config file as a kebab-case string (`"moving-average"`,
+/// `"exponential"`, `"median"`, `"peak-hold"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SmoothingMethod {
+    /// Mean of the last `smoothing_samples` samples (default)
+    #[default]
+    MovingAverage,
+    /// Exponential moving average with weight `smoothing_alpha`
+    Exponential,
+    /// Median of the last `smoothing_samples` samples
+    Median,
+    /// Follow peaks at once, then fall by `smoothing_decay` per sample
+    PeakHold,
+}
+
+impl SmoothingMethod {
+    /// All variants in display order — used for dropdown menus
+    pub const ALL: &'static [SmoothingMethod] = &[
+        SmoothingMethod::MovingAverage,
+        SmoothingMethod::Exponential,
+        SmoothingMethod::Median,
+        SmoothingMethod::PeakHold,
+    ];
+
+    /// Human-readable names corresponding to `ALL` — shown in the settings UI
+    pub const NAMES: &'static [&'static str] =
+        &["Moving Average", "Exponential", "Median", "Peak Hold"];
+}
+
+/// How a 0-100% metric maps onto the animation speed between `min_fps`
+/// and `max_fps`.
+///
+/// Stored in the config file as a kebab-case string (`"linear"`,
+/// `"logarithmic"`, `"exponential"`, `"smoothstep"`, `"stepped"`,
+/// `"custom"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpeedCurve {
+    /// Speed in direct proportion to the metric (default)
+    #[default]
+    Linear,
+    /// Quick to speed up at low values, flattening out towards 100% —
+    /// tells 5% from 20% apart on machines that are rarely busy
+    Logarithmic,
+    /// Barely moves at low values, racing only near 100%
+    Exponential,
+    /// Gentle at both ends, steepest in the middle
+    Smoothstep,
+    /// A few fixed speeds (`speed_curve_steps` of them)
+    Stepped,
+    /// Straight lines between the `speed_curve_points`
+    Custom,
+}
+
+impl SpeedCurve {
+    /// All variants in display order — used for dropdown menus
+    pub const ALL: &'static [SpeedCurve] = &[
+        SpeedCurve::Linear,
+        SpeedCurve::Logarithmic,
+        SpeedCurve::Exponential,
+        SpeedCurve::Smoothstep,
+        SpeedCurve::Stepped,
+        SpeedCurve::Custom,
     ];
+
+    /// Human-readable names corresponding to `ALL` — shown in the settings UI
+    pub const NAMES: &'static [&'static str] =
+        &["Linear", "Logarithmic", "Exponential", "Smoothstep", "Stepped", "Custom"];
+}
+
+/// One point of a custom speed curve.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CurvePoint {
+    /// Metric value, 0-100%
+    pub metric: f32,
+    /// Animation speed at that value (frames per second)
+    pub fps: f32,
+}
+
+/// How fast the cat runs for a 0-100% metric: the FPS range and the curve
+/// between its ends.
+///
+/// The flat `min_fps` / `max_fps` / `speed_curve*` fields of `Config` are
+/// the settings every source uses by default; `Config::source_speeds` can
+/// give individual sources their own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeedSettings {
+    /// Slowest the animation can run (frames per second)
+    pub min_fps: f32,
+    /// Fastest the animation can run (frames per second)
+    pub max_fps: f32,
+    /// How the metric maps onto the speed between `min_fps` and `max_fps`
+    #[serde(default)]
+    pub curve: SpeedCurve,
+    /// Number of distinct speeds in the stepped curve
+    #[serde(default = "default_speed_curve_steps")]
+    pub curve_steps: u32,
+    /// (metric %, fps) points of the custom curve
+    #[serde(default = "default_speed_curve_points")]
+    pub curve_points: Vec<CurvePoint>,
+}
+
+impl SpeedSettings {
+    /// Calculate how fast the cat should animate based on a 0-100% metric.
+    ///
+    /// The `curve` shapes the metric before interpolating between
+    /// `min_fps` (at 0%) and `max_fps` (at 100%); a custom curve gives the
+    /// speed directly.  Values outside 0-100 are clamped.
+    pub fn calculate_fps(&self, percent: f32) -> f32 {
+        let normalized = (percent / 100.0).clamp(0.0, 1.0);
+        let shaped = match self.curve {
+            SpeedCurve::Linear => normalized,
+            // log base 100, so 1% of the metric is already ~15% of the range
+            SpeedCurve::Logarithmic => (1.0 + 99.0 * normalized).log10() / 2.0,
+            SpeedCurve::Exponential => (100f32.powf(normalized) - 1.0) / 99.0,
+            SpeedCurve::Smoothstep => normalized * normalized * (3.0 - 2.0 * normalized),
+            SpeedCurve::Stepped => {
+                let steps = self.curve_steps.max(2) as f32;
+                (normalized * steps).floor().min(steps - 1.0) / (steps - 1.0)
+            }
+            SpeedCurve::Custom => {
+                return interpolate_points(&self.curve_points, normalized * 100.0)
+                    .unwrap_or(self.min_fps);
+            }
+        };
+        self.min_fps + shaped * (self.max_fps - self.min_fps)
+    }
+
+    /// Check the FPS range and curve parameters, putting any bad ones
+    /// right and recording an error for each.  `source` is the source these
+    /// settings belong to, or None for the shared defaults (the flat
+    /// `Config` fields), and only affects the field names reported.
+    fn repair(&mut self, source: Option<AnimationSource>, errors: &mut Vec<RunkatError>) {
+        // Errors name the field as it appears in config.json
+        let field = |name: &str| match source {
+            None => name.to_string(),
+            Some(source) => {
+                format!("{} for {}", name.trim_start_matches("speed_"), source.name())
+            }
+        };
+
+        check_range(errors, &field("min_fps"), &mut self.min_fps, MIN_FPS..=MAX_FPS, 2.0);
+        check_range(errors, &field("max_fps"), &mut self.max_fps, MIN_FPS..=MAX_FPS, 15.0);
+        if self.min_fps >= self.max_fps {
+            errors.push(invalid(
+                &field("min_fps"),
+                format!("({}) must be less than max_fps ({})", self.min_fps, self.max_fps),
+            ));
+            self.min_fps = MIN_FPS;
+            self.max_fps = self.max_fps.max(MIN_FPS + 1.0);
+        }
+
+        check_range(
+            errors,
+            &field("speed_curve_steps"),
+            &mut self.curve_steps,
+            2..=MAX_SPEED_CURVE_STEPS,
+            default_speed_curve_steps(),
+        );
+
+        if let Err(e) = validate_curve_points(&self.curve_points) {
+            errors.push(invalid(&field("speed_curve_points"), e));
+            self.curve_points = default_speed_curve_points();
+        }
+    }
+}
+
+/// Speed settings a single source uses instead of the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StoredSourceSpeed", into = "StoredSourceSpeed")]
+pub struct SourceSpeed {
+    /// Which metric these settings apply to
+    pub source: AnimationSource,
+    pub speed: SpeedSettings,
+}
+
+/// How a `SourceSpeed` is stored: the `SpeedSettings` fields next to
+/// `source`.  They're spelled out rather than flattened because the
+/// cosmic-config store (RON) can't read flattened fields back.
+#[derive(Serialize, Deserialize)]
+struct StoredSourceSpeed {
+    source: AnimationSource,
+    min_fps: f32,
+    max_fps: f32,
+    #[serde(default)]
+    curve: SpeedCurve,
+    #[serde(default = "default_speed_curve_steps")]
+    curve_steps: u32,
+    #[serde(default = "default_speed_curve_points")]
+    curve_points: Vec<CurvePoint>,
+}
+
+impl From<StoredSourceSpeed> for SourceSpeed {
+    fn from(stored: StoredSourceSpeed) -> Self {
+        Self {
+            source: stored.source,
+            speed: SpeedSettings {
+                min_fps: stored.min_fps,
+                max_fps: stored.max_fps,
+                curve: stored.curve,
+                curve_steps: stored.curve_steps,
+                curve_points: stored.curve_points,
+            },
+        }
+    }
+}
+
+impl From<SourceSpeed> for StoredSourceSpeed {
+    fn from(own: SourceSpeed) -> Self {
+        Self {
+            source: own.source,
+            min_fps: own.speed.min_fps,
+            max_fps: own.speed.max_fps,
+            curve: own.speed.curve,
+            curve_steps: own.speed.curve_steps,
+            curve_points: own.speed.curve_points,
+        }
+    }
+}
+
+/// The sleep threshold of one source, in its own units.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SourceThreshold {
+    /// Which metric the threshold applies to
+    pub source: AnimationSource,
+    /// The cat sleeps below this value
+    pub threshold: f32,
+}
+
+/// A named set of the settings people switch between several times a day
+/// (e.g. "Quiet", "Gaming", "Build server"): what drives the cat, when it
+/// sleeps and how fast it runs.
+///
+/// The active profile's settings live in the ordinary `Config` fields,
+/// where the settings page edits them; they are copied back into the
+/// profile when switching away from it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Name shown in the popup and settings, unique among profiles
+    pub name: String,
+    /// Which metric drives the animation
+    pub animation_source: AnimationSource,
+    /// Sleep threshold of every source
+    pub sleep_thresholds: Vec<SourceThreshold>,
+    /// Default FPS range and speed curve
+    pub speed: SpeedSettings,
+    /// Sources with their own FPS range and speed curve
+    #[serde(default)]
+    pub source_speeds: Vec<SourceSpeed>,
+}
+
+/// The weight of one metric in the mix.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MixWeight {
+    /// Which metric this weight applies to (anything except `Mix` itself)
+    pub source: AnimationSource,
+    /// How much the metric counts.  0 leaves it out entirely.
+    pub weight: f32,
+}
+
+/// One metric's part in a computed mix, for display in the popup.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MixContribution {
+    /// Which metric
+    pub source: AnimationSource,
+    /// The metric's own 0-100% value
+    pub value: f32,
+    /// The configured weight
+    pub weight: f32,
+    /// What the metric adds to the mix (weighted mode) or competes with
+    /// (max mode), in %
+    pub contribution: f32,
 }
 
 /// User-configurable application settings.
 ///
-/// Serialized to/from JSON on disk.  The `#[serde(default = "...")]`
-/// attributes ensure that missing fields (e.g. when upgrading from an older
-/// config version) get sensible defaults rather than causing parse errors.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Stored as one cosmic-config entry per field, or serialized to/from JSON
+/// on disk.  The `#[serde(default = "...")]` attributes ensure that missing
+/// fields (e.g. when upgrading from an older config version) get sensible
+/// defaults rather than causing parse errors.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, CosmicConfigEntry)]
+#[version = 2]
 pub struct Config {
+    /// Schema version the file was written with (see `CONFIG_VERSION`)
+    #[serde(default = "default_config_version")]
+    pub version: u32,
+
     /// Below this CPU usage %, the cat sleeps (CPU mode only).
     /// Range: 0-30 in the settings slider, but validated 0-100.
     #[serde(default = "default_cpu_threshold")]
     pub sleep_threshold_cpu: f32,
 
+    /// cgroup v2 to measure in CPU usage mode, e.g. `user.slice` or
+    /// `system.slice/docker-<id>.scope` (relative to `/sys/fs/cgroup`).
+    /// Empty measures the whole machine.
+    #[serde(default)]
+    pub cgroup_path: String,
+
     /// Below this frequency in MHz, the cat sleeps (frequency mode only)
     #[serde(default = "default_freq_threshold")]
     pub sleep_threshold_freq: f32,
@@ -65,18 +454,189 @@ pub struct Config {
     #[serde(default = "default_temp_threshold")]
     pub sleep_threshold_temp: f32,
 
-    /// Fastest the animation can run (frames per second)
+    /// Below this RAM usage %, the cat sleeps (memory mode only)
+    #[serde(default = "default_mem_threshold")]
+    pub sleep_threshold_mem: f32,
+
+    /// Below this throughput in KB/s, the cat sleeps (network mode only)
+    #[serde(default = "default_net_threshold")]
+    pub sleep_threshold_net: f32,
+
+    /// Combined rx+tx bandwidth in Mbit/s at which the cat runs flat out
+    /// (network mode only)
+    #[serde(default = "default_network_full_speed")]
+    pub network_full_speed_mbps: f32,
+
+    /// Interfaces to monitor (glob patterns like `"eth*"`).  Empty means
+    /// every interface not matched by `network_exclude`.
+    #[serde(default)]
+    pub network_include: Vec<String>,
+
+    /// Interfaces to ignore (glob patterns like `"docker*"`)
+    #[serde(default = "default_network_exclude")]
+    pub network_exclude: Vec<String>,
+
+    /// Below this utilisation % of the busiest disk, the cat sleeps
+    /// (disk mode only)
+    #[serde(default = "default_disk_threshold")]
+    pub sleep_threshold_disk: f32,
+
+    /// Block devices to ignore (glob patterns like `"loop*"`)
+    #[serde(default = "default_disk_exclude")]
+    pub disk_exclude: Vec<String>,
+
+    /// Whether partitions (e.g. `nvme0n1p1`) are listed alongside whole
+    /// disks.  Off by default, since a partition's I/O is already counted
+    /// in its parent disk.
+    #[serde(default)]
+    pub disk_include_partitions: bool,
+
+    /// Below this GPU load %, the cat sleeps (GPU mode only)
+    #[serde(default = "default_gpu_threshold")]
+    pub sleep_threshold_gpu: f32,
+
+    /// Below this PSI `some avg10` %, the cat sleeps (pressure mode only)
+    #[serde(default = "default_psi_threshold")]
+    pub sleep_threshold_psi: f32,
+
+    /// Below this 1-minute load per CPU (in %, so 100 = one runnable task
+    /// per CPU), the cat sleeps (load average mode only)
+    #[serde(default = "default_load_threshold")]
+    pub sleep_threshold_load: f32,
+
+    /// Below this many watts, the cat sleeps (power mode only)
+    #[serde(default = "default_power_threshold")]
+    pub sleep_threshold_power: f32,
+
+    /// Power draw in watts at which the cat runs at full speed
+    #[serde(default = "default_power_full_watts")]
+    pub power_full_watts: f32,
+
+    /// Below this combined %, the cat sleeps (mix mode only)
+    #[serde(default = "default_mix_threshold")]
+    pub sleep_threshold_mix: f32,
+
+    /// How the metrics in `mix_weights` are combined (mix mode only)
+    #[serde(default)]
+    pub mix_mode: MixMode,
+
+    /// Which metrics take part in the mix, and how much each counts
+    #[serde(default = "default_mix_weights")]
+    pub mix_weights: Vec<MixWeight>,
+
+    /// Shell command whose output drives the cat (command mode only).
+    /// Must print a number, or a JSON object with a `value` field.
+    #[serde(default)]
+    pub command_line: String,
+
+    /// Seconds between runs of `command_line`
+    #[serde(default = "default_command_interval")]
+    pub command_interval_secs: f32,
+
+    /// Seconds a run of `command_line` may take before it is killed
+    #[serde(default = "default_command_timeout")]
+    pub command_timeout_secs: f32,
+
+    /// Command value at which the cat runs at full speed
+    #[serde(default = "default_command_full_speed")]
+    pub command_full_speed: f32,
+
+    /// Below this command value, the cat sleeps (command mode only)
+    #[serde(default = "default_command_threshold")]
+    pub sleep_threshold_command: f32,
+
+    /// How far below its sleep threshold the metric must drop before a
+    /// running cat falls asleep again, as a % of the threshold.  Stops the
+    /// cat flickering when the metric hovers around the threshold.
+    #[serde(default = "default_sleep_hysteresis")]
+    pub sleep_hysteresis_pct: f32,
+
+    /// Shortest time in milliseconds the cat stays asleep or awake once it
+    /// has changed state
+    #[serde(default = "default_sleep_min_dwell")]
+    pub sleep_min_dwell_ms: u64,
+
+    /// Milliseconds between CPU usage samples.  Shorter makes the cat
+    /// twitchier; longer means fewer wakeups.
+    #[serde(default = "default_sample_interval")]
+    pub sample_interval_ms: u64,
+
+    /// How many samples the moving average and median filters look at
+    #[serde(default = "default_smoothing_samples")]
+    pub smoothing_samples: usize,
+
+    /// How CPU usage is smoothed
+    #[serde(default)]
+    pub smoothing_cpu: SmoothingMethod,
+
+    /// How CPU frequency is smoothed
+    #[serde(default = "default_sensor_smoothing")]
+    pub smoothing_freq: SmoothingMethod,
+
+    /// How CPU temperature is smoothed
+    #[serde(default = "default_sensor_smoothing")]
+    pub smoothing_temp: SmoothingMethod,
+
+    /// Weight of each new sample in the exponential filter (0-1, higher
+    /// follows changes faster)
+    #[serde(default = "default_smoothing_alpha")]
+    pub smoothing_alpha: f32,
+
+    /// Fraction the peak-hold filter falls by per sample (0-1)
+    #[serde(default = "default_smoothing_decay")]
+    pub smoothing_decay: f32,
+
+    /// Milliseconds between checks for config file and theme changes.
+    /// Only used if the files can't be watched for changes.
+    #[serde(default = "default_config_check_interval")]
+    pub config_check_interval_ms: u64,
+
+    /// Fastest the animation can run (frames per second), unless the
+    /// source has its own entry in `source_speeds`
     pub max_fps: f32,
 
-    /// Slowest the animation can run (frames per second)
+    /// Slowest the animation can run (frames per second), unless the
+    /// source has its own entry in `source_speeds`
     pub min_fps: f32,
 
+    /// How the metric maps onto the speed between `min_fps` and `max_fps`
+    #[serde(default)]
+    pub speed_curve: SpeedCurve,
+
+    /// Number of distinct speeds in the stepped curve
+    #[serde(default = "default_speed_curve_steps")]
+    pub speed_curve_steps: u32,
+
+    /// (metric %, fps) points of the custom curve, in increasing metric
+    /// order.  Speeds outside the first and last point stay level.
+    #[serde(default = "default_speed_curve_points")]
+    pub speed_curve_points: Vec<CurvePoint>,
+
+    /// Sources with their own FPS range and speed curve instead of the
+    /// ones above — e.g. a narrow range for slow-moving temperature
+    #[serde(default)]
+    pub source_speeds: Vec<SourceSpeed>,
+
     /// Whether to show "42%" next to the cat in CPU usage mode
     pub show_percentage: bool,
 
     /// Which system metric drives the animation and popup display
     #[serde(default)]
     pub animation_source: AnimationSource,
+
+    /// Saved sets of source, threshold and speed settings to switch between
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+
+    /// Name of the profile the current settings belong to, or empty if
+    /// they aren't saved as one
+    #[serde(default)]
+    pub active_profile: String,
+
+    /// Hours and weekdays during which the cat switches profile or stays
+    /// asleep; the first active one wins
+    #[serde(default)]
+    pub schedules: Vec<Schedule>,
 }
 
 // Default value functions for serde — called when a field is missing from
@@ -84,204 +644,1260 @@ pub struct Config {
 fn default_cpu_threshold() -> f32 { 5.0 }
 fn default_freq_threshold() -> f32 { 1000.0 }  // 1 GHz
 fn default_temp_threshold() -> f32 { 40.0 }     // 40 C
+fn default_mem_threshold() -> f32 { 20.0 }      // 20% RAM used
+fn default_net_threshold() -> f32 { 50.0 }      // 50 KB/s
+fn default_network_full_speed() -> f32 { 100.0 } // 100 Mbit/s
+fn default_network_exclude() -> Vec<String> {
+    ["lo", "docker*", "veth*"].iter().map(|s| s.to_string()).collect()
+}
+fn default_disk_threshold() -> f32 { 5.0 }      // 5% busy
+fn default_disk_exclude() -> Vec<String> {
+    ["loop*", "zram*", "ram*"].iter().map(|s| s.to_string()).collect()
+}
+fn default_gpu_threshold() -> f32 { 5.0 }       // 5% busy
+fn default_psi_threshold() -> f32 { 1.0 }       // 1% of time stalled
+fn default_load_threshold() -> f32 { 10.0 }     // 0.1 runnable tasks per CPU
+fn default_power_threshold() -> f32 { 5.0 }     // 5 W
+fn default_power_full_watts() -> f32 { 45.0 }  // a busy laptop package
+fn default_mix_threshold() -> f32 { 5.0 }
+fn default_command_interval() -> f32 { 5.0 }   // seconds
+fn default_command_timeout() -> f32 { 10.0 }   // seconds
+fn default_command_full_speed() -> f32 { 100.0 }
+fn default_command_threshold() -> f32 { 1.0 }
+fn default_config_version() -> u32 { CONFIG_VERSION }
+fn default_sleep_hysteresis() -> f32 { 20.0 }  // % of the threshold
+fn default_sleep_min_dwell() -> u64 { 1000 }   // ms
+fn default_sample_interval() -> u64 { CPU_SAMPLE_INTERVAL.as_millis() as u64 }
+fn default_smoothing_samples() -> usize { CPU_SAMPLE_COUNT }
+fn default_config_check_interval() -> u64 { CONFIG_CHECK_INTERVAL.as_millis() as u64 }
+fn default_sensor_smoothing() -> SmoothingMethod { SmoothingMethod::Exponential }
+fn default_smoothing_alpha() -> f32 { 0.3 }
+fn default_smoothing_decay() -> f32 { 0.1 }
+fn default_speed_curve_steps() -> u32 { 4 }
+fn default_speed_curve_points() -> Vec<CurvePoint> {
+    // Lively from the first few percent, then levelling off
+    vec![
+        CurvePoint { metric: 0.0, fps: 2.0 },
+        CurvePoint { metric: 10.0, fps: 8.0 },
+        CurvePoint { metric: 50.0, fps: 12.0 },
+        CurvePoint { metric: 100.0, fps: 15.0 },
+    ]
+}
+fn default_mix_weights() -> Vec<MixWeight> {
+    // Busy OR hot
+    vec![
+        MixWeight { source: AnimationSource::CpuUsage, weight: 1.0 },
+        MixWeight { source: AnimationSource::Temperature, weight: 1.0 },
+    ]
+}
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             sleep_threshold_cpu: 5.0,
+            cgroup_path: String::new(),
             sleep_threshold_freq: 1000.0,
             sleep_threshold_temp: 40.0,
+            sleep_threshold_mem: 20.0,
+            sleep_threshold_net: 50.0,
+            network_full_speed_mbps: 100.0,
+            network_include: Vec::new(),
+            network_exclude: default_network_exclude(),
+            sleep_threshold_disk: 5.0,
+            disk_exclude: default_disk_exclude(),
+            disk_include_partitions: false,
+            sleep_threshold_gpu: 5.0,
+            sleep_threshold_psi: 1.0,
+            sleep_threshold_load: 10.0,
+            sleep_threshold_power: 5.0,
+            power_full_watts: 45.0,
+            sleep_threshold_mix: 5.0,
+            mix_mode: MixMode::default(),
+            mix_weights: default_mix_weights(),
+            command_line: String::new(),
+            command_interval_secs: 5.0,
+            command_timeout_secs: 10.0,
+            command_full_speed: 100.0,
+            sleep_threshold_command: 1.0,
+            sleep_hysteresis_pct: default_sleep_hysteresis(),
+            sleep_min_dwell_ms: default_sleep_min_dwell(),
+            sample_interval_ms: default_sample_interval(),
+            smoothing_samples: default_smoothing_samples(),
+            smoothing_cpu: SmoothingMethod::default(),
+            smoothing_freq: default_sensor_smoothing(),
+            smoothing_temp: default_sensor_smoothing(),
+            smoothing_alpha: default_smoothing_alpha(),
+            smoothing_decay: default_smoothing_decay(),
+            config_check_interval_ms: default_config_check_interval(),
             max_fps: 15.0,
             min_fps: 2.0,
+            speed_curve: SpeedCurve::default(),
+            speed_curve_steps: default_speed_curve_steps(),
+            speed_curve_points: default_speed_curve_points(),
+            source_speeds: Vec::new(),
             show_percentage: true,
             animation_source: AnimationSource::default(),
+            profiles: Vec::new(),
+            active_profile: String::new(),
+            schedules: Vec::new(),
         }
     }
 }
 
+/// The applet instance this process belongs to, if it isn't the default one
+struct Instance {
+    /// Instance id as given on the command line
+    id: String,
+    /// cosmic-config name of its store (`CONFIG_ID.<id>`)
+    store_id: String,
+}
+
+/// Set once at startup by `Config::set_instance()`
+static INSTANCE: OnceLock<Instance> = OnceLock::new();
+
 impl Config {
     /// Get the sleep threshold for whichever animation source is currently
     /// selected.  For example, if the user is in CPU mode, returns the
     /// CPU threshold; in frequency mode, the frequency threshold, etc.
     pub fn current_threshold(&self) -> f32 {
-        match self.animation_source {
+        self.threshold(self.animation_source)
+    }
+
+    /// Get the sleep threshold of a source, in its own units
+    pub fn threshold(&self, source: AnimationSource) -> f32 {
+        match source {
             AnimationSource::CpuUsage => self.sleep_threshold_cpu,
             AnimationSource::Frequency => self.sleep_threshold_freq,
             AnimationSource::Temperature => self.sleep_threshold_temp,
+            AnimationSource::Memory => self.sleep_threshold_mem,
+            AnimationSource::Network => self.sleep_threshold_net,
+            AnimationSource::DiskIo => self.sleep_threshold_disk,
+            AnimationSource::Gpu => self.sleep_threshold_gpu,
+            AnimationSource::Pressure => self.sleep_threshold_psi,
+            AnimationSource::LoadAverage => self.sleep_threshold_load,
+            AnimationSource::Power => self.sleep_threshold_power,
+            AnimationSource::Mix => self.sleep_threshold_mix,
+            AnimationSource::Command => self.sleep_threshold_command,
         }
     }
 
     /// Set the sleep threshold for the currently selected animation source.
     /// Called from the settings window when the user moves the slider.
     pub fn set_current_threshold(&mut self, value: f32) {
-        match self.animation_source {
+        self.set_threshold(self.animation_source, value);
+    }
+
+    /// Set the sleep threshold of a source
+    pub fn set_threshold(&mut self, source: AnimationSource, value: f32) {
+        match source {
             AnimationSource::CpuUsage => self.sleep_threshold_cpu = value,
             AnimationSource::Frequency => self.sleep_threshold_freq = value,
             AnimationSource::Temperature => self.sleep_threshold_temp = value,
+            AnimationSource::Memory => self.sleep_threshold_mem = value,
+            AnimationSource::Network => self.sleep_threshold_net = value,
+            AnimationSource::DiskIo => self.sleep_threshold_disk = value,
+            AnimationSource::Gpu => self.sleep_threshold_gpu = value,
+            AnimationSource::Pressure => self.sleep_threshold_psi = value,
+            AnimationSource::LoadAverage => self.sleep_threshold_load = value,
+            AnimationSource::Power => self.sleep_threshold_power = value,
+            AnimationSource::Mix => self.sleep_threshold_mix = value,
+            AnimationSource::Command => self.sleep_threshold_command = value,
         }
     }
 
-    /// Full path to the config JSON file.
-    ///
-    /// Uses the standard XDG config directory (`~/.config/cosmic-runkat/config.json`).
-    pub fn config_path() -> PathBuf {
-        dirs::config_dir()
-            .map(|d| d.join("cosmic-runkat"))
-            .unwrap_or_else(|| PathBuf::from("/tmp/cosmic-runkat"))
-            .join("config.json")
+    /// Shortest time the cat stays asleep or awake once it has changed
+    pub fn sleep_min_dwell(&self) -> Duration {
+        Duration::from_millis(self.sleep_min_dwell_ms)
     }
 
-    /// Load configuration from disk.
-    ///
-    /// If the config file exists and is valid, returns it.
-    /// If it's missing or invalid, returns `Config::default()`.
-    pub fn load() -> Self {
-        let path = Self::config_path();
+    /// Time between CPU usage samples
+    pub fn sample_interval(&self) -> Duration {
+        Duration::from_millis(self.sample_interval_ms)
+    }
 
-        if path.exists() {
-            if let Some(mut config) = Self::load_from_path(&path) {
-                if let Err(e) = config.validate() {
-                    tracing::warn!("Invalid config: {}. Using defaults.", e);
-                    config = Self::default();
-                }
-                return config;
-            }
+    /// Time between checks for config file and theme changes
+    pub fn config_check_interval(&self) -> Duration {
+        Duration::from_millis(self.config_check_interval_ms)
+    }
+
+    /// How a source is smoothed, or None for sources that aren't (they are
+    /// either averaged by the kernel already, or rates over a whole sample
+    /// interval).
+    pub fn smoothing_method(&self, source: AnimationSource) -> Option<SmoothingMethod> {
+        match source {
+            AnimationSource::CpuUsage => Some(self.smoothing_cpu),
+            AnimationSource::Frequency => Some(self.smoothing_freq),
+            AnimationSource::Temperature => Some(self.smoothing_temp),
+            _ => None,
         }
+    }
 
-        Self::default()
+    /// Set how a source is smoothed (ignored for sources that aren't)
+    pub fn set_smoothing_method(&mut self, source: AnimationSource, method: SmoothingMethod) {
+        match source {
+            AnimationSource::CpuUsage => self.smoothing_cpu = method,
+            AnimationSource::Frequency => self.smoothing_freq = method,
+            AnimationSource::Temperature => self.smoothing_temp = method,
+            _ => {}
+        }
     }
 
-    /// Try to read and parse a config file at the given path.
-    /// Returns `None` and logs errors if the file can't be read or parsed.
-    fn load_from_path(path: &Path) -> Option<Self> {
-        let content = fs::read_to_string(path)
-            .map_err(|e| tracing::error!("Failed to read config file: {}", e))
-            .ok()?;
+    /// The filter to apply to a source's samples, with its parameters
+    pub fn smoothing_filter(&self, source: AnimationSource) -> Filter {
+        match self.smoothing_method(source).unwrap_or_default() {
+            SmoothingMethod::MovingAverage => {
+                Filter::MovingAverage { window: self.smoothing_samples }
+            }
+            SmoothingMethod::Exponential => Filter::Exponential { alpha: self.smoothing_alpha },
+            SmoothingMethod::Median => Filter::Median { window: self.smoothing_samples },
+            SmoothingMethod::PeakHold => Filter::PeakHold { decay: self.smoothing_decay },
+        }
+    }
 
-        serde_json::from_str(&content)
-            .map_err(|e| tracing::error!("Failed to parse config file: {}", e))
-            .ok()
-            .inspect(|_| tracing::debug!("Loaded config from {:?}", path))
+    /// The speed settings every source uses unless it has its own
+    pub fn default_speed(&self) -> SpeedSettings {
+        SpeedSettings {
+            min_fps: self.min_fps,
+            max_fps: self.max_fps,
+            curve: self.speed_curve,
+            curve_steps: self.speed_curve_steps,
+            curve_points: self.speed_curve_points.clone(),
+        }
     }
 
-    /// Write the config to disk as pretty-printed JSON.
-    /// Validates before saving to avoid writing corrupt data.
-    pub fn save(&self) -> Result<(), std::io::Error> {
-        self.validate()
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    /// Whether a source has its own speed settings
+    pub fn has_own_speed(&self, source: AnimationSource) -> bool {
+        self.source_speeds.iter().any(|s| s.source == source)
+    }
 
-        let path = Self::config_path();
-        // Ensure the parent directory exists (e.g. on first run)
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        let content = serde_json::to_string_pretty(self)?;
-        fs::write(path, content)
+    /// The speed settings a source runs with: its own, or the defaults
+    pub fn speed_settings(&self, source: AnimationSource) -> SpeedSettings {
+        self.source_speeds
+            .iter()
+            .find(|s| s.source == source)
+            .map(|s| s.speed.clone())
+            .unwrap_or_else(|| self.default_speed())
     }
 
-    /// Check that all config values are within acceptable ranges.
-    /// Returns `Err(message)` describing the first invalid value found.
-    pub fn validate(&self) -> Result<(), String> {
-        if !(0.0..=100.0).contains(&self.sleep_threshold_cpu) {
-            return Err(format!(
-                "sleep_threshold_cpu must be between 0 and 100, got {}",
-                self.sleep_threshold_cpu
-            ));
+    /// Give a source its own speed settings (starting from the defaults),
+    /// or go back to the defaults
+    pub fn set_own_speed(&mut self, source: AnimationSource, own: bool) {
+        if own && !self.has_own_speed(source) {
+            let speed = self.default_speed();
+            self.source_speeds.push(SourceSpeed { source, speed });
+        } else if !own {
+            self.source_speeds.retain(|s| s.source != source);
         }
+    }
 
-        if !(0.0..=10000.0).contains(&self.sleep_threshold_freq) {
-            return Err(format!(
-                "sleep_threshold_freq must be between 0 and 10000 MHz, got {}",
-                self.sleep_threshold_freq
-            ));
+    /// Change the speed settings a source runs with — its own if it has
+    /// them, otherwise the defaults (which affects every such source)
+    pub fn update_speed(&mut self, source: AnimationSource, f: impl FnOnce(&mut SpeedSettings)) {
+        if let Some(own) = self.source_speeds.iter_mut().find(|s| s.source == source) {
+            f(&mut own.speed);
+            return;
         }
+        let mut speed = self.default_speed();
+        f(&mut speed);
+        self.update_speed_defaults(speed);
+    }
 
-        if !(0.0..=150.0).contains(&self.sleep_threshold_temp) {
-            return Err(format!(
-                "sleep_threshold_temp must be between 0 and 150\u{00b0}C, got {}",
-                self.sleep_threshold_temp
-            ));
+    /// Store the shared default speed settings in the flat fields
+    fn update_speed_defaults(&mut self, speed: SpeedSettings) {
+        self.min_fps = speed.min_fps;
+        self.max_fps = speed.max_fps;
+        self.speed_curve = speed.curve;
+        self.speed_curve_steps = speed.curve_steps;
+        self.speed_curve_points = speed.curve_points;
+    }
+
+    /// The current source, threshold and speed settings as a profile
+    pub fn capture_profile(&self, name: &str) -> Profile {
+        Profile {
+            name: name.to_string(),
+            animation_source: self.animation_source,
+            sleep_thresholds: AnimationSource::ALL
+                .iter()
+                .map(|&source| SourceThreshold { source, threshold: self.threshold(source) })
+                .collect(),
+            speed: self.default_speed(),
+            source_speeds: self.source_speeds.clone(),
         }
+    }
 
-        if !(MIN_FPS..=MAX_FPS).contains(&self.min_fps) {
-            return Err(format!(
-                "min_fps must be between {} and {}, got {}",
-                MIN_FPS, MAX_FPS, self.min_fps
-            ));
+    /// Put a profile's settings into effect (without making it active).
+    /// Sources the profile has no threshold for keep their current one.
+    fn apply_profile(&mut self, profile: &Profile) {
+        self.animation_source = profile.animation_source;
+        for t in &profile.sleep_thresholds {
+            self.set_threshold(t.source, t.threshold);
         }
+        self.update_speed_defaults(profile.speed.clone());
+        self.source_speeds = profile.source_speeds.clone();
+    }
 
-        if !(MIN_FPS..=MAX_FPS).contains(&self.max_fps) {
-            return Err(format!(
-                "max_fps must be between {} and {}, got {}",
-                MIN_FPS, MAX_FPS, self.max_fps
-            ));
+    /// Save the current settings as a profile (replacing any profile of
+    /// the same name) and make it the active one.
+    pub fn save_profile(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Profile name can't be empty".to_string());
+        }
+        let profile = self.capture_profile(name);
+        match self.profiles.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
         }
+        self.active_profile = name.to_string();
+        Ok(())
+    }
 
-        if self.min_fps >= self.max_fps {
-            return Err(format!(
-                "min_fps ({}) must be less than max_fps ({})",
-                self.min_fps, self.max_fps
-            ));
+    /// Switch to another profile.  Changes made to the current settings
+    /// since switching to the active profile are kept in that profile
+    /// first, so switching back restores them.
+    pub fn switch_profile(&mut self, name: &str) -> Result<(), String> {
+        let Some(profile) = self.profiles.iter().find(|p| p.name == name).cloned() else {
+            return Err(format!("No profile named {name}"));
+        };
+
+        let current = self.capture_profile(&self.active_profile);
+        if let Some(active) = self.profiles.iter_mut().find(|p| p.name == current.name) {
+            *active = current;
         }
 
+        self.apply_profile(&profile);
+        self.active_profile = profile.name;
         Ok(())
     }
 
-    /// Calculate how fast the cat should animate based on a 0-100% metric.
-    ///
-    /// Uses linear interpolation: at 0% -> `min_fps`, at 100% -> `max_fps`.
-    /// Values outside 0-100 are clamped.
-    pub fn calculate_fps(&self, cpu_percent: f32) -> f32 {
-        let normalized = (cpu_percent / 100.0).clamp(0.0, 1.0);
-        self.min_fps + normalized * (self.max_fps - self.min_fps)
+    /// Delete a profile.  The current settings stay as they are.
+    pub fn delete_profile(&mut self, name: &str) -> Result<(), String> {
+        let count = self.profiles.len();
+        self.profiles.retain(|p| p.name != name);
+        if self.profiles.len() == count {
+            return Err(format!("No profile named {name}"));
+        }
+        if self.active_profile == name {
+            self.active_profile.clear();
+        }
+        Ok(())
     }
-}
 
-// ---------------------------------------------------------------------------
-// Tests
-// ---------------------------------------------------------------------------
+    /// The weight of a metric in the mix (0 if it isn't part of it)
+    pub fn mix_weight(&self, source: AnimationSource) -> f32 {
+        self.mix_weights
+            .iter()
+            .find(|w| w.source == source)
+            .map(|w| w.weight)
+            .unwrap_or(0.0)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Set the weight of a metric in the mix, adding it if needed
+    pub fn set_mix_weight(&mut self, source: AnimationSource, weight: f32) {
+        match self.mix_weights.iter_mut().find(|w| w.source == source) {
+            Some(w) => w.weight = weight,
+            None => self.mix_weights.push(MixWeight { source, weight }),
+        }
+    }
 
-    #[test]
-    fn test_config_validation_valid() {
-        let config = Config::default();
-        assert!(config.validate().is_ok());
+    /// Work out what each weighted metric contributes to the mix.
+    ///
+    /// `percentage_of` returns the current 0-100% value of a metric — the
+    /// applet passes in the same figure it would use for that source on
+    /// its own.  Metrics with zero weight are skipped.
+    pub fn mix_contributions(
+        &self,
+        percentage_of: impl Fn(AnimationSource) -> f32,
+    ) -> Vec<MixContribution> {
+        let active = self
+            .mix_weights
+            .iter()
+            .filter(|w| w.weight > 0.0 && w.source != AnimationSource::Mix);
+        let total_weight: f32 = active.clone().map(|w| w.weight).sum();
+
+        active
+            .map(|w| {
+                let value = percentage_of(w.source).clamp(0.0, 100.0);
+                let contribution = match self.mix_mode {
+                    MixMode::Weighted => value * w.weight / total_weight,
+                    MixMode::Max => (value * w.weight).min(100.0),
+                };
+                MixContribution { source: w.source, value, weight: w.weight, contribution }
+            })
+            .collect()
     }
 
-    #[test]
-    fn test_config_validation_cpu_threshold_too_low() {
-        let mut config = Config::default();
-        config.sleep_threshold_cpu = -5.0;
-        assert!(config.validate().is_err());
+    /// Combine contributions into the single 0-100% value that drives the cat.
+    pub fn mix_metric(&self, contributions: &[MixContribution]) -> f32 {
+        let combined = match self.mix_mode {
+            MixMode::Weighted => contributions.iter().map(|c| c.contribution).sum(),
+            MixMode::Max => contributions.iter().map(|c| c.contribution).fold(0.0f32, f32::max),
+        };
+        combined.clamp(0.0, 100.0)
     }
 
-    #[test]
-    fn test_config_validation_cpu_threshold_too_high() {
-        let mut config = Config::default();
-        config.sleep_threshold_cpu = 150.0;
-        assert!(config.validate().is_err());
+    /// Whether a source's metric drives the cat: it is the animation
+    /// source, or has a weight in the mix.
+    pub fn source_in_use(&self, source: AnimationSource) -> bool {
+        self.animation_source == source
+            || (self.animation_source == AnimationSource::Mix && self.mix_weight(source) > 0.0)
     }
 
-    #[test]
-    fn test_config_validation_min_fps_invalid() {
-        let mut config = Config::default();
-        config.min_fps = 0.5;
-        assert!(config.validate().is_err());
+    /// Use the config of the given applet instance, instead of the default
+    /// one, for the rest of the process.  Call it once at startup, before
+    /// anything is loaded.  Ids are made of letters, digits, `-` and `_`.
+    pub fn set_instance(id: &str) -> Result<(), RunkatError> {
+        let valid = !id.is_empty()
+            && id.len() <= MAX_INSTANCE_ID_LEN
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(RunkatError::Config(format!(
+                "invalid instance id {:?}: use up to {} letters, digits, '-' and '_'",
+                id, MAX_INSTANCE_ID_LEN
+            )));
+        }
 
-        config = Config::default();
-        config.min_fps = 35.0;
-        assert!(config.validate().is_err());
+        let instance = Instance { id: id.to_string(), store_id: format!("{}.{}", CONFIG_ID, id) };
+        INSTANCE
+            .set(instance)
+            .map_err(|_| RunkatError::Config("the instance is already set".to_string()))
     }
 
-    #[test]
-    fn test_config_validation_fps_order_invalid() {
-        let mut config = Config::default();
-        config.min_fps = 20.0;
-        config.max_fps = 10.0;
-        assert!(config.validate().is_err());
+    /// Id of the applet instance whose config is used, or None for the
+    /// default instance
+    pub fn instance() -> Option<&'static str> {
+        INSTANCE.get().map(|instance| instance.id.as_str())
+    }
+
+    /// cosmic-config name of the store: `CONFIG_ID`, or `CONFIG_ID.<id>`
+    /// for another instance
+    pub fn store_id() -> &'static str {
+        INSTANCE.get().map_or(CONFIG_ID, |instance| instance.store_id.as_str())
+    }
+
+    /// Full path to the config JSON file, used before the cosmic-config
+    /// store (and instead of it if the store can't be opened).
+    ///
+    /// Uses the standard XDG config directory (`~/.config/cosmic-runkat/config.json`,
+    /// or `config-<id>.json` for another instance).
+    pub fn config_path() -> PathBuf {
+        let name = match Self::instance() {
+            Some(id) => format!("config-{}.json", id),
+            None => "config.json".to_string(),
+        };
+        dirs::config_dir()
+            .map(|d| d.join("cosmic-runkat"))
+            .unwrap_or_else(|| PathBuf::from("/tmp/cosmic-runkat"))
+            .join(name)
+    }
+
+    /// Full path the JSON file is renamed to once it has been imported into
+    /// the store (`config.json.imported`).
+    pub fn imported_path() -> PathBuf {
+        Self::config_path().with_extension("json.imported")
+    }
+
+    /// Open the cosmic-config store, or None if it can't be (e.g. there's
+    /// no config directory), in which case the JSON file is used instead.
+    pub fn store() -> Option<cosmic_config::Config> {
+        cosmic_config::Config::new(Self::store_id(), Self::VERSION)
+            .map_err(|e| tracing::warn!("Can't open the config store, using config.json: {}", e))
+            .ok()
+    }
+
+    /// Load configuration from disk.
+    ///
+    /// Returns the stored config with any invalid fields put right (see
+    /// `load_checked()`).  Settings that were never stored, or a JSON file
+    /// that's missing or not JSON at all, give `Config::default()`.
+    pub fn load() -> Self {
+        Self::load_checked().0
+    }
+
+    /// Load configuration from disk, also returning what was wrong with it
+    /// and where the rejected file was copied to, if this load copied it.
+    ///
+    /// A single bad field doesn't cost the rest of the file: fields of the
+    /// wrong type go back to their defaults and out-of-range ones are
+    /// repaired (see `repair()`), each reported as a
+    /// `RunkatError::ConfigValidation`.  A file that can't be read or
+    /// isn't JSON gives the defaults and that error.
+    pub fn load_checked() -> (Self, Vec<RunkatError>, Option<PathBuf>) {
+        if let Some(store) = Self::store() {
+            return Self::load_from_store(&store);
+        }
+        match Self::reload() {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                // Nothing retries this, and the defaults may be saved over
+                // the file later
+                tracing::warn!("{}. Using defaults.", e);
+                let backup = back_up_rejected(&Self::config_path());
+                (Self::default(), vec![e], backup)
+            }
+        }
+    }
+
+    /// Re-read the JSON config after it changed on disk.
+    ///
+    /// Like `load_checked()`, but a file that can't be read or parsed is an
+    /// error so the caller can keep its current config: an editor may be
+    /// half-way through writing it, so it isn't backed up either.  A
+    /// missing file means the defaults.
+    pub fn reload() -> Result<(Self, Vec<RunkatError>, Option<PathBuf>), RunkatError> {
+        let path = Self::config_path();
+        if !path.exists() {
+            return Ok((Self::default(), Vec::new(), None));
+        }
+
+        let (config, problems) = Self::load_from_path(&path)?;
+        // Only a repair changes what the file says
+        let backup = if problems.is_empty() { None } else { back_up_rejected(&path) };
+        Ok((config, problems, backup))
+    }
+
+    /// Read the config from the store, importing `config.json` into it first
+    /// if that hasn't been done yet.
+    fn load_from_store(
+        store: &cosmic_config::Config,
+    ) -> (Self, Vec<RunkatError>, Option<PathBuf>) {
+        let (mut problems, backup) = Self::import_json(store);
+        let (config, store_problems) = match Self::get_entry(store) {
+            Ok(config) => Self::checked(config, Vec::new()),
+            Err((errors, config)) => Self::checked(config, errors),
+        };
+        problems.extend(store_problems);
+        (config, problems, backup)
+    }
+
+    /// Repair a config read from the store, returning what was wrong with
+    /// it.  `errors` are the store's errors for entries that couldn't be
+    /// read (which keep their defaults); entries that were simply never
+    /// stored aren't problems.
+    pub fn checked(
+        mut config: Self,
+        errors: Vec<cosmic_config::Error>,
+    ) -> (Self, Vec<RunkatError>) {
+        let mut problems: Vec<RunkatError> = errors
+            .into_iter()
+            .filter(cosmic_config::Error::is_err)
+            .map(|e| RunkatError::Config(format!("{}, using the default", e)))
+            .collect();
+        problems.extend(config.repair());
+
+        for problem in &problems {
+            tracing::warn!("{}", problem);
+        }
+        (config, problems)
+    }
+
+    /// Copy `config.json` into the store if it's still there, then rename
+    /// it to `config.json.imported` so it's only imported once.  A file
+    /// that can't be read is left in place (and kept as
+    /// `config.json.rejected`) and the store is used as it is.  Returns the
+    /// problems found and the copy, if one was made.
+    fn import_json(store: &cosmic_config::Config) -> (Vec<RunkatError>, Option<PathBuf>) {
+        let path = Self::config_path();
+        if !path.exists() {
+            return (Vec::new(), None);
+        }
+
+        let (config, mut problems) = match Self::load_from_path(&path) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                tracing::warn!("{}. Not importing {:?}.", e, path);
+                return (vec![e], back_up_rejected(&path));
+            }
+        };
+        let mut stamped = config;
+        stamped.version = CONFIG_VERSION;
+        if let Err(e) = stamped.write_entry(store) {
+            tracing::error!("Failed to import {:?} into the config store: {}", path, e);
+            problems.push(RunkatError::Config(format!("failed to import config.json: {}", e)));
+            return (problems, None);
+        }
+
+        match fs::rename(&path, Self::imported_path()) {
+            Ok(()) => tracing::info!("Imported {:?} into the config store", path),
+            Err(e) => tracing::error!("Failed to rename imported {:?}: {}", path, e),
+        }
+        (problems, None)
+    }
+
+    /// Try to read and parse a config file at the given path, migrating it
+    /// from older versions and repairing invalid fields.
+    fn load_from_path(path: &Path) -> Result<(Self, Vec<RunkatError>), RunkatError> {
+        let content = fs::read_to_string(path)?;
+        let (mut config, mut problems) = Self::from_json(&content)?;
+        problems.extend(config.repair());
+
+        for problem in &problems {
+            tracing::warn!("{}", problem);
+        }
+        tracing::debug!("Loaded config from {:?}", path);
+        Ok((config, problems))
+    }
+
+    /// Parse config JSON written by any version, migrating it to the
+    /// current one.  Fields that don't parse (e.g. a string where a number
+    /// belongs) keep their defaults and are returned as errors.
+    fn from_json(content: &str) -> Result<(Self, Vec<RunkatError>), RunkatError> {
+        let value = serde_json::from_str(content)
+            .map_err(|e| RunkatError::Config(format!("config file is not valid JSON: {}", e)))?;
+        let value = migrate(value);
+        if let Ok(config) = serde_json::from_value(value.clone()) {
+            return Ok((config, Vec::new()));
+        }
+
+        let Value::Object(fields) = value else {
+            return Err(RunkatError::Config("config file is not a JSON object".to_string()));
+        };
+        let Ok(Value::Object(mut merged)) = serde_json::to_value(Self::default()) else {
+            return Err(RunkatError::Config("default config can't be serialised".to_string()));
+        };
+
+        // Lay the file's fields over the defaults one at a time, dropping
+        // any that stop the whole thing from parsing
+        let mut problems = Vec::new();
+        for (key, field) in fields {
+            let previous = merged.insert(key.clone(), field);
+            if let Err(e) = serde_json::from_value::<Self>(Value::Object(merged.clone())) {
+                problems.push(invalid(&key, format!("is not valid ({}), using the default", e)));
+                match previous {
+                    Some(previous) => merged.insert(key, previous),
+                    None => merged.remove(&key),
+                };
+            }
+        }
+
+        let config = serde_json::from_value(Value::Object(merged))
+            .map_err(|e| RunkatError::Config(format!("config file is not valid: {}", e)))?;
+        Ok((config, problems))
+    }
+
+    /// Write the config to the store, or to disk as pretty-printed JSON if
+    /// the store can't be opened.
+    /// Validates before saving to avoid writing corrupt data.
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        match Self::store() {
+            Some(store) => self.save_to_store(&store),
+            None => self.save_to_path(&Self::config_path()),
+        }
+    }
+
+    /// Write every field to its store entry in one transaction.
+    fn save_to_store(&self, store: &cosmic_config::Config) -> Result<(), std::io::Error> {
+        self.validate()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))?;
+
+        let mut stamped = self.clone();
+        stamped.version = CONFIG_VERSION;
+        stamped.write_entry(store).map_err(|e| std::io::Error::other(e.to_string()))
+    }
+
+    /// Write the config to the given path, stamped with the current
+    /// version.  The file is replaced atomically: the JSON goes to a temp
+    /// file in the same directory which is then renamed over the old one.
+    fn save_to_path(&self, path: &Path) -> Result<(), std::io::Error> {
+        self.validate()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))?;
+
+        // Ensure the parent directory exists (e.g. on first run)
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut stamped = self.clone();
+        stamped.version = CONFIG_VERSION;
+        let content = serde_json::to_string_pretty(&stamped)?;
+
+        // Named per process so the settings window and CLI can't collide
+        let tmp_path = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        let result = fs::File::create(&tmp_path)
+            .and_then(|mut file| {
+                file.write_all(content.as_bytes())?;
+                // Make sure the data is on disk before the rename makes it
+                // the config
+                file.sync_all()
+            })
+            .and_then(|()| fs::rename(&tmp_path, path));
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
+        result
+    }
+
+    /// Check that all config values are within acceptable ranges.
+    /// Returns the first invalid field found, without changing anything.
+    pub fn validate(&self) -> Result<(), RunkatError> {
+        match self.clone().repair().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Put every invalid field right, keeping the valid ones: numbers out
+    /// of range are clamped to the nearest valid value, anything else goes
+    /// back to its default.  Returns an error for each field changed.
+    pub fn repair(&mut self) -> Vec<RunkatError> {
+        let mut errors = Vec::new();
+        let e = &mut errors;
+
+        check_range(
+            e,
+            "sleep_threshold_cpu",
+            &mut self.sleep_threshold_cpu,
+            0.0..=100.0,
+            default_cpu_threshold(),
+        );
+        check_range(
+            e,
+            "sleep_threshold_freq",
+            &mut self.sleep_threshold_freq,
+            0.0..=10000.0,
+            default_freq_threshold(),
+        );
+        check_range(
+            e,
+            "sleep_threshold_temp",
+            &mut self.sleep_threshold_temp,
+            0.0..=150.0,
+            default_temp_threshold(),
+        );
+        check_range(
+            e,
+            "sleep_threshold_mem",
+            &mut self.sleep_threshold_mem,
+            0.0..=100.0,
+            default_mem_threshold(),
+        );
+        check_range(
+            e,
+            "sleep_threshold_net",
+            &mut self.sleep_threshold_net,
+            0.0..=1_000_000.0,
+            default_net_threshold(),
+        );
+        check_range(
+            e,
+            "network_full_speed_mbps",
+            &mut self.network_full_speed_mbps,
+            1.0..=100_000.0,
+            default_network_full_speed(),
+        );
+        check_range(
+            e,
+            "sleep_threshold_disk",
+            &mut self.sleep_threshold_disk,
+            0.0..=100.0,
+            default_disk_threshold(),
+        );
+        check_range(
+            e,
+            "sleep_threshold_gpu",
+            &mut self.sleep_threshold_gpu,
+            0.0..=100.0,
+            default_gpu_threshold(),
+        );
+        check_range(
+            e,
+            "sleep_threshold_psi",
+            &mut self.sleep_threshold_psi,
+            0.0..=100.0,
+            default_psi_threshold(),
+        );
+        check_range(
+            e,
+            "sleep_threshold_load",
+            &mut self.sleep_threshold_load,
+            0.0..=100.0,
+            default_load_threshold(),
+        );
+        check_range(
+            e,
+            "sleep_threshold_power",
+            &mut self.sleep_threshold_power,
+            0.0..=500.0,
+            default_power_threshold(),
+        );
+        check_range(
+            e,
+            "power_full_watts",
+            &mut self.power_full_watts,
+            1.0..=500.0,
+            default_power_full_watts(),
+        );
+        check_range(
+            e,
+            "sleep_threshold_mix",
+            &mut self.sleep_threshold_mix,
+            0.0..=100.0,
+            default_mix_threshold(),
+        );
+
+        if self.cgroup_path.split('/').any(|part| part == "..") {
+            e.push(invalid(
+                "cgroup_path",
+                format!("must stay inside /sys/fs/cgroup, got {}", self.cgroup_path),
+            ));
+            self.cgroup_path = String::new();
+        }
+
+        check_range(
+            e,
+            "command_interval_secs",
+            &mut self.command_interval_secs,
+            0.5..=3600.0,
+            default_command_interval(),
+        );
+        check_range(
+            e,
+            "command_timeout_secs",
+            &mut self.command_timeout_secs,
+            0.1..=600.0,
+            default_command_timeout(),
+        );
+
+        if !(self.command_full_speed.is_finite() && self.command_full_speed > 0.0) {
+            e.push(invalid(
+                "command_full_speed",
+                format!("must be greater than 0, got {}", self.command_full_speed),
+            ));
+            self.command_full_speed = default_command_full_speed();
+        }
+
+        if !self.sleep_threshold_command.is_finite() {
+            e.push(invalid(
+                "sleep_threshold_command",
+                format!("must be a number, got {}", self.sleep_threshold_command),
+            ));
+            self.sleep_threshold_command = default_command_threshold();
+        }
+
+        check_range(
+            e,
+            "sleep_hysteresis_pct",
+            &mut self.sleep_hysteresis_pct,
+            0.0..=MAX_SLEEP_HYSTERESIS_PCT,
+            default_sleep_hysteresis(),
+        );
+        check_range(
+            e,
+            "sleep_min_dwell_ms",
+            &mut self.sleep_min_dwell_ms,
+            0..=MAX_SLEEP_DWELL_MS,
+            default_sleep_min_dwell(),
+        );
+        check_range(
+            e,
+            "sample_interval_ms",
+            &mut self.sample_interval_ms,
+            MIN_INTERVAL_MS..=MAX_INTERVAL_MS,
+            default_sample_interval(),
+        );
+        check_range(
+            e,
+            "smoothing_samples",
+            &mut self.smoothing_samples,
+            1..=MAX_SMOOTHING_SAMPLES,
+            default_smoothing_samples(),
+        );
+
+        if !(self.smoothing_alpha > 0.0 && self.smoothing_alpha <= 1.0) {
+            e.push(invalid(
+                "smoothing_alpha",
+                format!("must be greater than 0 and at most 1, got {}", self.smoothing_alpha),
+            ));
+            self.smoothing_alpha =
+                if self.smoothing_alpha > 1.0 { 1.0 } else { default_smoothing_alpha() };
+        }
+
+        if !(0.0..1.0).contains(&self.smoothing_decay) {
+            e.push(invalid(
+                "smoothing_decay",
+                format!("must be at least 0 and less than 1, got {}", self.smoothing_decay),
+            ));
+            self.smoothing_decay = default_smoothing_decay();
+        }
+
+        check_range(
+            e,
+            "config_check_interval_ms",
+            &mut self.config_check_interval_ms,
+            MIN_INTERVAL_MS..=MAX_INTERVAL_MS,
+            default_config_check_interval(),
+        );
+
+        if self.mix_weights.iter().any(|w| w.source == AnimationSource::Mix) {
+            e.push(invalid("mix_weights", "cannot include the mix itself".to_string()));
+            self.mix_weights.retain(|w| w.source != AnimationSource::Mix);
+        }
+        for w in &mut self.mix_weights {
+            let field = format!("mix weight for {}", w.source.name());
+            check_range(e, &field, &mut w.weight, 0.0..=10.0, 1.0);
+        }
+
+        let mut speed = self.default_speed();
+        speed.repair(None, e);
+        self.update_speed_defaults(speed);
+
+        let mut seen = Vec::new();
+        self.source_speeds.retain(|own| {
+            if seen.contains(&own.source) {
+                e.push(invalid(
+                    "source_speeds",
+                    format!("has more than one entry for {}", own.source.name()),
+                ));
+                return false;
+            }
+            seen.push(own.source);
+            true
+        });
+        for own in &mut self.source_speeds {
+            own.speed.repair(Some(own.source), e);
+        }
+
+        // Profiles: unique, non-empty names, and settings that would pass
+        // the checks above once switched to
+        let mut names: Vec<String> = Vec::new();
+        self.profiles.retain(|profile| {
+            if profile.name.trim().is_empty() || names.contains(&profile.name) {
+                e.push(invalid(
+                    "profiles",
+                    format!("has an empty or repeated name {:?}", profile.name),
+                ));
+                return false;
+            }
+            names.push(profile.name.clone());
+            true
+        });
+        if !self.active_profile.is_empty() && !names.contains(&self.active_profile) {
+            e.push(invalid(
+                "active_profile",
+                format!("names no profile, got {:?}", self.active_profile),
+            ));
+            self.active_profile.clear();
+        }
+        // Schedules are checked below against every profile name; checked
+        // here, where there are no profiles, each would fail
+        let mut base = self.clone();
+        base.profiles.clear();
+        base.active_profile.clear();
+        base.schedules.clear();
+        for profile in &mut self.profiles {
+            let mut scratch = base.clone();
+            scratch.apply_profile(profile);
+            for error in scratch.repair() {
+                e.push(match error {
+                    RunkatError::ConfigValidation { field, message } => invalid(
+                        &format!("{} in profile {}", field, profile.name),
+                        message,
+                    ),
+                    other => other,
+                });
+            }
+            *profile = scratch.capture_profile(&profile.name);
+        }
+
+        // A schedule can only switch to a profile that exists
+        self.schedules.retain(|schedule| match &schedule.action {
+            ScheduleAction::Profile(profile) if !names.contains(profile) => {
+                e.push(invalid(
+                    &format!("schedule {}", schedule.name),
+                    format!("switches to profile {profile:?}, which doesn't exist"),
+                ));
+                false
+            }
+            _ => true,
+        });
+
+        errors
+    }
+
+    /// Calculate how fast the cat should animate based on a 0-100% metric
+    /// of the selected animation source, using that source's speed
+    /// settings.
+    pub fn calculate_fps(&self, percent: f32) -> f32 {
+        self.speed_settings(self.animation_source).calculate_fps(percent)
+    }
+}
+
+/// Keep a copy of a config file that had to be repaired or couldn't be
+/// read, so nothing the user wrote is lost when the repaired config is
+/// saved over it.  An existing copy is never replaced: a later file may
+/// already be the repaired one.  Returns the copy's path if one was made.
+fn back_up_rejected(path: &Path) -> Option<PathBuf> {
+    let backup = path.with_extension("json.rejected");
+    let copied = fs::File::open(path).and_then(|mut original| {
+        let mut copy = fs::OpenOptions::new().write(true).create_new(true).open(&backup)?;
+        std::io::copy(&mut original, &mut copy)
+    });
+    match copied {
+        Ok(_) => {
+            tracing::warn!("Kept a copy of the rejected config at {:?}", backup);
+            Some(backup)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            tracing::debug!("Keeping the earlier rejected config at {:?}", backup);
+            None
+        }
+        Err(e) => {
+            tracing::error!("Failed to back up rejected config: {}", e);
+            None
+        }
+    }
+}
+
+/// A step that rewrites a raw config from one version to the next.
+type Migration = fn(&mut Map<String, Value>);
+
+/// Migration steps in order: `MIGRATIONS[i]` upgrades version `i + 1` to
+/// `i + 2`, so there is one fewer than `CONFIG_VERSION`.
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+/// Bring raw config JSON up to `CONFIG_VERSION` by running every migration
+/// step after the version it was written with.  A file from a newer
+/// version is left alone; fields this version doesn't know are ignored.
+fn migrate(mut value: Value) -> Value {
+    let Some(config) = value.as_object_mut() else {
+        return value; // not an object; parsing will report it
+    };
+
+    // Files written before versioning are version 1
+    let version = config.get("version").and_then(Value::as_u64).unwrap_or(1);
+    if version > u64::from(CONFIG_VERSION) {
+        tracing::warn!(
+            "Config is from a newer version ({} > {}); some settings may be ignored",
+            version,
+            CONFIG_VERSION
+        );
+        return value;
+    }
+
+    for (from, step) in MIGRATIONS.iter().enumerate().skip(version.saturating_sub(1) as usize) {
+        tracing::info!("Migrating config from version {} to {}", from + 1, from + 2);
+        step(config);
+    }
+    config.insert("version".to_string(), CONFIG_VERSION.into());
+    value
+}
+
+/// Version 2: the single v1.x `sleep_threshold` (a CPU %) became one
+/// threshold per source.
+fn migrate_v1_to_v2(config: &mut Map<String, Value>) {
+    if let Some(threshold) = config.remove("sleep_threshold") {
+        config.entry("sleep_threshold_cpu").or_insert(threshold);
+    }
+}
+
+/// A `ConfigValidation` error for a field
+fn invalid(field: &str, message: String) -> RunkatError {
+    RunkatError::ConfigValidation { field: field.to_string(), message }
+}
+
+/// Check that a number is within `range`, recording an error and clamping
+/// it into the range if not (or setting it to `default` if it isn't a
+/// number at all, e.g. NaN).
+fn check_range<T>(
+    errors: &mut Vec<RunkatError>,
+    field: &str,
+    value: &mut T,
+    range: std::ops::RangeInclusive<T>,
+    default: T,
+) where
+    T: PartialOrd + Copy + std::fmt::Display,
+{
+    if range.contains(value) {
+        return;
+    }
+    errors.push(invalid(
+        field,
+        format!("must be between {} and {}, got {}", range.start(), range.end(), value),
+    ));
+    *value = if *value < *range.start() {
+        *range.start()
+    } else if *value > *range.end() {
+        *range.end()
+    } else {
+        default
+    };
+}
+
+/// Check a custom speed curve: at least two points, metrics within 0-100
+/// in strictly increasing order, and speeds within the allowed FPS range.
+fn validate_curve_points(points: &[CurvePoint]) -> Result<(), String> {
+    if points.len() < 2 {
+        return Err(format!("needs at least 2 points, got {}", points.len()));
+    }
+    for (i, point) in points.iter().enumerate() {
+        if !(0.0..=100.0).contains(&point.metric) {
+            return Err(format!(
+                "metric must be between 0 and 100, got {}",
+                point.metric
+            ));
+        }
+        if !(MIN_FPS..=MAX_FPS).contains(&point.fps) {
+            return Err(format!(
+                "fps must be between {} and {}, got {}",
+                MIN_FPS, MAX_FPS, point.fps
+            ));
+        }
+        if i > 0 && point.metric <= points[i - 1].metric {
+            return Err(format!(
+                "must be in increasing metric order, got {} after {}",
+                point.metric,
+                points[i - 1].metric
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Read the speed at `metric` off a piecewise-linear curve, staying level
+/// before the first point and after the last.  `None` for no points.
+fn interpolate_points(points: &[CurvePoint], metric: f32) -> Option<f32> {
+    let first = points.first()?;
+    let last = points.last()?;
+    if metric <= first.metric {
+        return Some(first.fps);
+    }
+    let fps = points
+        .windows(2)
+        .find(|pair| metric <= pair[1].metric)
+        .map(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+            let t = (metric - a.metric) / (b.metric - a.metric);
+            a.fps + t * (b.fps - a.fps)
+        })
+        .unwrap_or(last.fps);
+    Some(fps)
+}
+
+/// Parse a custom speed curve written as `metric:fps` pairs separated by
+/// commas, e.g. `"0:2, 10:8, 100:15"`.  Used by the settings text fields.
+pub fn parse_curve_points(text: &str) -> Result<Vec<CurvePoint>, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (metric, fps) = pair
+                .split_once(':')
+                .ok_or_else(|| format!("expected metric:fps, got \"{pair}\""))?;
+            let number = |s: &str| {
+                s.trim().parse::<f32>().map_err(|_| format!("not a number: \"{}\"", s.trim()))
+            };
+            Ok(CurvePoint { metric: number(metric)?, fps: number(fps)? })
+        })
+        .collect()
+}
+
+/// Write a custom speed curve in the form `parse_curve_points` reads
+pub fn format_curve_points(points: &[CurvePoint]) -> String {
+    points
+        .iter()
+        .map(|p| format!("{}:{}", p.metric, p.fps))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Whether a network interface should be counted, according to the
+/// `network_include` / `network_exclude` patterns.  An empty `include`
+/// means all; exclusions win.
+pub fn interface_selected(include: &[String], exclude: &[String], name: &str) -> bool {
+    let included = include.is_empty() || include.iter().any(|p| pattern_matches(p, name));
+    included && !exclude.iter().any(|p| pattern_matches(p, name))
+}
+
+/// Whether a block device should be monitored, according to
+/// `disk_exclude` and `disk_include_partitions`.
+pub fn disk_selected(
+    exclude: &[String],
+    include_partitions: bool,
+    name: &str,
+    is_partition: bool,
+) -> bool {
+    (include_partitions || !is_partition) && !exclude.iter().any(|p| pattern_matches(p, name))
+}
+
+/// Match a name against a simple glob pattern where `*` stands for any
+/// run of characters (e.g. `"veth*"` matches `"veth1a2b"`).  Patterns without
+/// `*` must match exactly.
+pub fn pattern_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // `split` always yields at least one item
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*` at all — the whole name must have been consumed
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::TimeOfDay;
+
+    #[test]
+    fn test_config_validation_valid() {
+        let config = Config::default();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_validation_cpu_threshold_too_low() {
+        let mut config = Config::default();
+        config.sleep_threshold_cpu = -5.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validation_cpu_threshold_too_high() {
+        let mut config = Config::default();
+        config.sleep_threshold_cpu = 150.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validation_mem_threshold_invalid() {
+        let mut config = Config::default();
+        config.sleep_threshold_mem = 120.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validation_min_fps_invalid() {
+        let mut config = Config::default();
+        config.min_fps = 0.5;
+        assert!(config.validate().is_err());
+
+        config = Config::default();
+        config.min_fps = 35.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validation_fps_order_invalid() {
+        let mut config = Config::default();
+        config.min_fps = 20.0;
+        config.max_fps = 10.0;
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -297,6 +1913,126 @@ mod tests {
         assert!((config.calculate_fps(100.0) - config.max_fps).abs() < 0.01);
     }
 
+    #[test]
+    fn test_speed_curves() {
+        let mut config = Config::default();
+
+        // Every curve runs from min_fps at 0% to max_fps at 100%
+        for &curve in SpeedCurve::ALL {
+            config.speed_curve = curve;
+            config.speed_curve_points = vec![
+                CurvePoint { metric: 0.0, fps: config.min_fps },
+                CurvePoint { metric: 100.0, fps: config.max_fps },
+            ];
+            assert!((config.calculate_fps(0.0) - config.min_fps).abs() < 0.01, "{curve:?}");
+            assert!((config.calculate_fps(100.0) - config.max_fps).abs() < 0.01, "{curve:?}");
+        }
+
+        // Logarithmic tells 5% and 20% apart far better than linear
+        config.speed_curve = SpeedCurve::Logarithmic;
+        let log_gap = config.calculate_fps(20.0) - config.calculate_fps(5.0);
+        config.speed_curve = SpeedCurve::Linear;
+        let linear_gap = config.calculate_fps(20.0) - config.calculate_fps(5.0);
+        assert!(log_gap > linear_gap * 1.5);
+
+        config.speed_curve = SpeedCurve::Exponential;
+        assert!(config.calculate_fps(50.0) < config.min_fps + 2.0);
+
+        config.speed_curve = SpeedCurve::Smoothstep;
+        assert!((config.calculate_fps(50.0) - 8.5).abs() < 0.01);
+
+        // 4 steps of 2, 6.33, 10.67 and 15 fps
+        config.speed_curve = SpeedCurve::Stepped;
+        config.speed_curve_steps = 4;
+        assert!((config.calculate_fps(10.0) - 2.0).abs() < 0.01);
+        assert!((config.calculate_fps(30.0) - 6.333).abs() < 0.01);
+        assert!((config.calculate_fps(74.0) - 10.667).abs() < 0.01);
+        assert!((config.calculate_fps(76.0) - 15.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_custom_speed_curve() {
+        let mut config = Config::default();
+        config.speed_curve = SpeedCurve::Custom;
+        config.speed_curve_points = parse_curve_points("10:4, 20:10, 80:12").unwrap();
+
+        assert!((config.calculate_fps(0.0) - 4.0).abs() < 0.01);
+        assert!((config.calculate_fps(15.0) - 7.0).abs() < 0.01);
+        assert!((config.calculate_fps(50.0) - 11.0).abs() < 0.01);
+        assert!((config.calculate_fps(95.0) - 12.0).abs() < 0.01);
+        assert_eq!(format_curve_points(&config.speed_curve_points), "10:4, 20:10, 80:12");
+        assert!(config.validate().is_ok());
+
+        assert!(parse_curve_points("10-4").is_err());
+        assert!(parse_curve_points("10:fast").is_err());
+
+        config.speed_curve_points = parse_curve_points("50:4, 20:10").unwrap();
+        assert!(config.validate().is_err());
+        config.speed_curve_points = parse_curve_points("0:4").unwrap();
+        assert!(config.validate().is_err());
+        config.speed_curve_points = parse_curve_points("0:4, 100:99").unwrap();
+        assert!(config.validate().is_err());
+
+        config = Config::default();
+        config.speed_curve_steps = 1;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_per_source_speed() {
+        let mut config = Config::default();
+        config.animation_source = AnimationSource::Temperature;
+        assert!(!config.has_own_speed(AnimationSource::Temperature));
+
+        // A narrow range just for temperature
+        config.set_own_speed(AnimationSource::Temperature, true);
+        config.update_speed(AnimationSource::Temperature, |speed| {
+            speed.min_fps = 4.0;
+            speed.max_fps = 6.0;
+        });
+        assert!((config.calculate_fps(0.0) - 4.0).abs() < 0.01);
+        assert!((config.calculate_fps(100.0) - 6.0).abs() < 0.01);
+
+        // Other sources keep the defaults
+        config.animation_source = AnimationSource::CpuUsage;
+        assert!((config.calculate_fps(100.0) - 15.0).abs() < 0.01);
+        config.update_speed(AnimationSource::CpuUsage, |speed| speed.max_fps = 20.0);
+        assert!((config.max_fps - 20.0).abs() < f32::EPSILON);
+        assert!(config.validate().is_ok());
+
+        // Invalid and duplicate entries are rejected
+        config.update_speed(AnimationSource::Temperature, |speed| speed.min_fps = 7.0);
+        assert!(config.validate().is_err());
+        config.update_speed(AnimationSource::Temperature, |speed| speed.min_fps = 4.0);
+        let duplicate = config.source_speeds[0].clone();
+        config.source_speeds.push(duplicate);
+        assert!(config.validate().is_err());
+
+        config.set_own_speed(AnimationSource::Temperature, false);
+        assert!(config.source_speeds.is_empty());
+    }
+
+    #[test]
+    fn test_source_speeds_deserialise() {
+        // Old flat configs still load, with no per-source settings
+        let config: Config = serde_json::from_str(
+            r#"{"max_fps": 12, "min_fps": 3, "show_percentage": true}"#,
+        )
+        .unwrap();
+        assert!(config.source_speeds.is_empty());
+        assert_eq!(config.speed_settings(AnimationSource::Gpu), config.default_speed());
+
+        let config: Config = serde_json::from_str(
+            r#"{"max_fps": 12, "min_fps": 3, "show_percentage": true,
+                "source_speeds": [{"source": "temperature", "min_fps": 4, "max_fps": 6}]}"#,
+        )
+        .unwrap();
+        let speed = config.speed_settings(AnimationSource::Temperature);
+        assert_eq!(speed.curve, SpeedCurve::Linear);
+        assert!((speed.max_fps - 6.0).abs() < f32::EPSILON);
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_current_threshold() {
         let mut config = Config::default();
@@ -309,6 +2045,9 @@ mod tests {
 
         config.animation_source = AnimationSource::Temperature;
         assert!((config.current_threshold() - 40.0).abs() < f32::EPSILON);
+
+        config.animation_source = AnimationSource::Memory;
+        assert!((config.current_threshold() - 20.0).abs() < f32::EPSILON);
     }
 
     #[test]
@@ -324,6 +2063,486 @@ mod tests {
         assert!((config.sleep_threshold_freq - 2000.0).abs() < f32::EPSILON);
     }
 
+    #[test]
+    fn test_config_validation_mix_weights() {
+        let mut config = Config::default();
+        config.set_mix_weight(AnimationSource::Memory, 20.0);
+        assert!(config.validate().is_err());
+
+        config = Config::default();
+        config.set_mix_weight(AnimationSource::Mix, 1.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validation_cgroup_path() {
+        let mut config = Config::default();
+        config.cgroup_path = "user.slice/user-1000.slice".to_string();
+        assert!(config.validate().is_ok());
+
+        config.cgroup_path = "user.slice/../../etc".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validation_command() {
+        let mut config = Config::default();
+        config.command_interval_secs = 0.0;
+        assert!(config.validate().is_err());
+
+        config = Config::default();
+        config.command_full_speed = 0.0;
+        assert!(config.validate().is_err());
+
+        config = Config::default();
+        config.sleep_threshold_command = f32::NAN;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validation_sampling() {
+        let mut config = Config::default();
+        config.sample_interval_ms = 50;
+        assert!(config.validate().is_err());
+
+        config = Config::default();
+        config.smoothing_samples = 0;
+        assert!(config.validate().is_err());
+
+        config = Config::default();
+        config.config_check_interval_ms = 60_000;
+        assert!(config.validate().is_err());
+
+        // Older config files without the fields get the defaults
+        let config: Config =
+            serde_json::from_str(r#"{"max_fps": 15, "min_fps": 2, "show_percentage": true}"#)
+                .unwrap();
+        assert_eq!(config.sample_interval(), CPU_SAMPLE_INTERVAL);
+        assert_eq!(config.smoothing_samples, CPU_SAMPLE_COUNT);
+    }
+
+    #[test]
+    fn test_config_validation_hysteresis() {
+        let mut config = Config::default();
+        config.sleep_hysteresis_pct = -1.0;
+        assert!(config.validate().is_err());
+
+        config = Config::default();
+        config.sleep_hysteresis_pct = 100.0;
+        assert!(config.validate().is_err());
+
+        config = Config::default();
+        config.sleep_min_dwell_ms = MAX_SLEEP_DWELL_MS + 1;
+        assert!(config.validate().is_err());
+
+        config.sleep_min_dwell_ms = 0;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_smoothing() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.smoothing_filter(AnimationSource::CpuUsage),
+            Filter::MovingAverage { window: CPU_SAMPLE_COUNT }
+        );
+        assert_eq!(config.smoothing_method(AnimationSource::Memory), None);
+
+        config.set_smoothing_method(AnimationSource::Temperature, SmoothingMethod::PeakHold);
+        config.smoothing_decay = 0.25;
+        assert_eq!(
+            config.smoothing_filter(AnimationSource::Temperature),
+            Filter::PeakHold { decay: 0.25 }
+        );
+
+        config.smoothing_alpha = 0.0;
+        assert!(config.validate().is_err());
+
+        config = Config::default();
+        config.smoothing_decay = 1.0;
+        assert!(config.validate().is_err());
+
+        let config: Config = serde_json::from_str(
+            r#"{"max_fps": 15, "min_fps": 2, "show_percentage": true, "smoothing_freq": "median"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.smoothing_freq, SmoothingMethod::Median);
+        assert_eq!(config.smoothing_temp, SmoothingMethod::Exponential);
+    }
+
+    #[test]
+    fn test_mix_weighted() {
+        let mut config = Config::default();
+        config.mix_mode = MixMode::Weighted;
+        config.set_mix_weight(AnimationSource::CpuUsage, 3.0);
+        config.set_mix_weight(AnimationSource::Temperature, 1.0);
+
+        // CPU 40% (weight 3) and temp 80% (weight 1): (120 + 80) / 4 = 50%
+        let contributions = config.mix_contributions(|s| match s {
+            AnimationSource::CpuUsage => 40.0,
+            AnimationSource::Temperature => 80.0,
+            _ => 0.0,
+        });
+        assert_eq!(contributions.len(), 2);
+        assert!((contributions[0].contribution - 30.0).abs() < 0.01);
+        assert!((contributions[1].contribution - 20.0).abs() < 0.01);
+        assert!((config.mix_metric(&contributions) - 50.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_mix_max() {
+        let config = Config::default();
+        assert_eq!(config.mix_mode, MixMode::Max);
+
+        // Idle but hot: the temperature wins
+        let contributions = config.mix_contributions(|s| match s {
+            AnimationSource::CpuUsage => 5.0,
+            AnimationSource::Temperature => 70.0,
+            _ => 0.0,
+        });
+        assert!((config.mix_metric(&contributions) - 70.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_mix_empty() {
+        let mut config = Config::default();
+        config.mix_weights.clear();
+        let contributions = config.mix_contributions(|_| 50.0);
+        assert!(contributions.is_empty());
+        assert_eq!(config.mix_metric(&contributions), 0.0);
+    }
+
+    #[test]
+    fn test_source_name() {
+        assert_eq!(AnimationSource::CpuUsage.name(), "CPU Usage");
+        assert_eq!(AnimationSource::Mix.name(), "Weighted Mix");
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("lo", "lo"));
+        assert!(!pattern_matches("lo", "lo0"));
+        assert!(pattern_matches("docker*", "docker0"));
+        assert!(pattern_matches("veth*", "veth"));
+        assert!(pattern_matches("*0", "eth0"));
+        assert!(pattern_matches("br-*-x", "br-1234-x"));
+        assert!(!pattern_matches("docker*", "eth0"));
+    }
+
+    #[test]
+    fn test_interface_selected() {
+        let mut config = Config::default();
+        let selected = |c: &Config, name: &str| {
+            interface_selected(&c.network_include, &c.network_exclude, name)
+        };
+        assert!(selected(&config, "eth0"));
+        assert!(!selected(&config, "lo"));
+        assert!(!selected(&config, "veth3f2a"));
+
+        config.network_include = vec!["wl*".to_string()];
+        assert!(selected(&config, "wlp2s0"));
+        assert!(!selected(&config, "eth0"));
+    }
+
+    #[test]
+    fn test_disk_selected() {
+        let mut config = Config::default();
+        let selected = |c: &Config, name: &str, is_partition: bool| {
+            disk_selected(&c.disk_exclude, c.disk_include_partitions, name, is_partition)
+        };
+        assert!(selected(&config, "nvme0n1", false));
+        assert!(!selected(&config, "nvme0n1p1", true));
+        assert!(!selected(&config, "loop3", false));
+        assert!(!selected(&config, "zram0", false));
+
+        config.disk_include_partitions = true;
+        assert!(selected(&config, "nvme0n1p1", true));
+    }
+
+    #[test]
+    fn test_source_in_use() {
+        let mut config = Config::default();
+        assert!(config.source_in_use(AnimationSource::CpuUsage));
+        assert!(!config.source_in_use(AnimationSource::Memory));
+
+        config.animation_source = AnimationSource::Mix;
+        config.set_mix_weight(AnimationSource::Memory, 1.0);
+        config.set_mix_weight(AnimationSource::Gpu, 0.0);
+        assert!(config.source_in_use(AnimationSource::Memory));
+        assert!(!config.source_in_use(AnimationSource::Gpu));
+    }
+
+    #[test]
+    fn test_profiles_switch() {
+        let mut config = Config::default();
+        config.save_profile("Work").unwrap();
+
+        config.animation_source = AnimationSource::Temperature;
+        config.sleep_threshold_temp = 55.0;
+        config.max_fps = 8.0;
+        config.save_profile("Quiet").unwrap();
+        assert_eq!(config.active_profile, "Quiet");
+
+        config.switch_profile("Work").unwrap();
+        assert_eq!(config.animation_source, AnimationSource::CpuUsage);
+        assert!((config.sleep_threshold_temp - 40.0).abs() < f32::EPSILON);
+        assert!((config.max_fps - 15.0).abs() < f32::EPSILON);
+
+        // Tweaks to the active profile survive switching away and back
+        config.sleep_threshold_cpu = 12.0;
+        config.switch_profile("Quiet").unwrap();
+        assert!((config.sleep_threshold_cpu - 5.0).abs() < f32::EPSILON);
+        assert!((config.max_fps - 8.0).abs() < f32::EPSILON);
+        config.switch_profile("Work").unwrap();
+        assert!((config.sleep_threshold_cpu - 12.0).abs() < f32::EPSILON);
+
+        assert!(config.switch_profile("Gaming").is_err());
+        assert!(config.save_profile("  ").is_err());
+
+        config.delete_profile("Work").unwrap();
+        assert!(config.active_profile.is_empty());
+        assert_eq!(config.profiles.len(), 1);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_switch_profile_with_schedule_saves() {
+        let mut config = Config::default();
+        config.save_profile("Work").unwrap();
+        config.animation_source = AnimationSource::Temperature;
+        config.sleep_threshold_temp = 55.0;
+        config.set_own_speed(AnimationSource::Temperature, true);
+        config.update_speed(AnimationSource::Temperature, |speed| speed.max_fps = 8.0);
+        config.save_profile("Quiet").unwrap();
+        config.schedules.push(profile_schedule("Quiet"));
+
+        // Switching as the popup, settings page and CLI do
+        config.switch_profile("Work").unwrap();
+        config.switch_profile("Quiet").unwrap();
+        let quiet = config.profiles.iter().find(|p| p.name == "Quiet").unwrap().clone();
+        assert_eq!(config.capture_profile("Quiet"), quiet);
+
+        // Applying a captured profile and capturing it again changes nothing
+        let mut other = Config::default();
+        other.apply_profile(&quiet);
+        assert_eq!(other.capture_profile("Quiet"), quiet);
+
+        assert!(config.validate().is_ok());
+        let dir = std::env::temp_dir()
+            .join(format!("cosmic-runkat-config-switch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let saved = config.save_to_path(&dir.join("config.json"));
+        let _ = fs::remove_dir_all(&dir);
+        assert!(saved.is_ok(), "{saved:?}");
+    }
+
+    #[test]
+    fn test_repair_profiles() {
+        let mut config = Config::default();
+        config.save_profile("Quiet").unwrap();
+        config.profiles.push(config.profiles[0].clone());
+        config.profiles[0].speed.max_fps = 99.0;
+        config.active_profile = "Gaming".to_string();
+
+        let errors: Vec<String> = config.repair().iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors.len(), 3, "{errors:?}");
+        assert!(errors[2].contains("max_fps in profile Quiet"));
+        assert_eq!(config.profiles.len(), 1);
+        assert!((config.profiles[0].speed.max_fps - MAX_FPS).abs() < f32::EPSILON);
+        assert!(config.active_profile.is_empty());
+    }
+
+    /// A working-hours schedule that switches to `profile`
+    fn profile_schedule(profile: &str) -> Schedule {
+        Schedule {
+            name: "Work".to_string(),
+            days: Vec::new(),
+            start: TimeOfDay::parse("09:00").unwrap(),
+            end: TimeOfDay::parse("17:00").unwrap(),
+            action: ScheduleAction::Profile(profile.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_profile_schedule_is_valid() {
+        let mut config = Config::default();
+        config.save_profile("Quiet").unwrap();
+        config.schedules.push(profile_schedule("Quiet"));
+
+        let errors: Vec<String> = config.repair().iter().map(|e| e.to_string()).collect();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(config.schedules.len(), 1);
+
+        let dir = std::env::temp_dir()
+            .join(format!("cosmic-runkat-config-schedule-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let saved = config.save_to_path(&dir.join("config.json"));
+        let _ = fs::remove_dir_all(&dir);
+        assert!(saved.is_ok(), "{saved:?}");
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let mut config = Config::default();
+        config.save_profile("Quiet").unwrap();
+        config.schedules.push(profile_schedule("Quiet"));
+        config.schedules.push(Schedule { action: ScheduleAction::Sleep, ..profile_schedule("") });
+        config.set_own_speed(AnimationSource::Temperature, true);
+        config.update_speed(AnimationSource::Temperature, |speed| {
+            speed.curve = SpeedCurve::Stepped;
+        });
+
+        // The store keeps each field as RON, written like this
+        let stored = ron::ser::to_string_pretty(&config, ron::ser::PrettyConfig::new()).unwrap();
+        let loaded: Config = ron::from_str(&stored).unwrap();
+        assert_eq!(loaded, config);
+    }
+
+    #[test]
+    fn test_migrations_cover_every_version() {
+        assert_eq!(MIGRATIONS.len() as u32, CONFIG_VERSION - 1);
+    }
+
+    #[test]
+    fn test_invalid_instance_ids_are_rejected() {
+        // Only invalid ids: a valid one would switch every other test to it
+        for id in ["", "cpu/temp", "../other", "has space", &"x".repeat(65)] {
+            assert!(Config::set_instance(id).is_err(), "{id:?}");
+        }
+        assert_eq!(Config::instance(), None);
+        assert_eq!(Config::store_id(), CONFIG_ID);
+    }
+
+    #[test]
+    fn test_store_version_matches_config_version() {
+        assert_eq!(Config::VERSION, u64::from(CONFIG_VERSION));
+    }
+
+    #[test]
+    fn test_migrate_unversioned_config() {
+        let (config, _) = Config::from_json(r#"{"sleep_threshold": 12, "max_fps": 20}"#).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert!((config.sleep_threshold_cpu - 12.0).abs() < f32::EPSILON);
+        assert!((config.max_fps - 20.0).abs() < f32::EPSILON);
+
+        // A threshold already in the new field wins over the legacy one
+        let (config, _) =
+            Config::from_json(r#"{"sleep_threshold": 12, "sleep_threshold_cpu": 3}"#).unwrap();
+        assert!((config.sleep_threshold_cpu - 3.0).abs() < f32::EPSILON);
+
+        // Current files aren't touched
+        let json = format!(r#"{{"version": {}, "sleep_threshold": 12}}"#, CONFIG_VERSION);
+        let (config, _) = Config::from_json(&json).unwrap();
+        assert!((config.sleep_threshold_cpu - 5.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_save_is_atomic_and_versioned() {
+        let dir = std::env::temp_dir()
+            .join(format!("cosmic-runkat-config-save-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("config.json");
+
+        let mut config = Config::default();
+        config.version = 1;
+        config.max_fps = 22.0;
+        config.save_to_path(&path).unwrap();
+
+        let (loaded, problems) = Config::load_from_path(&path).unwrap();
+        assert!(problems.is_empty());
+        assert_eq!(loaded.version, CONFIG_VERSION);
+        assert!((loaded.max_fps - 22.0).abs() < f32::EPSILON);
+
+        // Only the config is left behind, no temp files
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        // An invalid config is refused and the old file is kept
+        config.max_fps = -1.0;
+        assert!(config.save_to_path(&path).is_err());
+        assert!(Config::load_from_path(&path).is_ok());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_back_up_rejected_keeps_first_copy() {
+        let dir = std::env::temp_dir()
+            .join(format!("cosmic-runkat-config-rejected-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+
+        fs::write(&path, r#"{"max_fps": -1}"#).unwrap();
+        assert_eq!(back_up_rejected(&path), Some(dir.join("config.json.rejected")));
+        fs::write(&path, r#"{"max_fps": 10"#).unwrap();
+        assert_eq!(back_up_rejected(&path), None);
+
+        let kept = fs::read_to_string(dir.join("config.json.rejected")).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(kept, r#"{"max_fps": -1}"#);
+    }
+
+    #[test]
+    fn test_repair_keeps_valid_fields() {
+        let mut config = Config::default();
+        config.sleep_threshold_cpu = 500.0;
+        config.smoothing_alpha = f32::NAN;
+        config.cgroup_path = "../etc".to_string();
+        config.max_fps = 22.0;
+
+        let errors = config.repair();
+        let fields: Vec<_> = errors
+            .iter()
+            .map(|e| match e {
+                RunkatError::ConfigValidation { field, .. } => field.as_str(),
+                _ => panic!("unexpected error {e}"),
+            })
+            .collect();
+        assert_eq!(fields, ["sleep_threshold_cpu", "cgroup_path", "smoothing_alpha"]);
+
+        // Out of range is clamped, nonsense goes back to the default
+        assert!((config.sleep_threshold_cpu - 100.0).abs() < f32::EPSILON);
+        assert!((config.smoothing_alpha - 0.3).abs() < f32::EPSILON);
+        assert!(config.cgroup_path.is_empty());
+        // Everything else is untouched
+        assert!((config.max_fps - 22.0).abs() < f32::EPSILON);
+        assert!(config.validate().is_ok());
+        assert!(config.repair().is_empty());
+    }
+
+    #[test]
+    fn test_repair_speed_settings() {
+        let mut config = Config::default();
+        config.set_own_speed(AnimationSource::Gpu, true);
+        config.update_speed(AnimationSource::Gpu, |speed| speed.curve_steps = 99);
+        config.min_fps = 20.0;
+        config.max_fps = 10.0;
+        config.source_speeds.push(config.source_speeds[0].clone());
+
+        let errors: Vec<String> = config.repair().iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors.len(), 3, "{errors:?}");
+        assert!(errors[0].contains("min_fps"));
+        assert!(errors[1].contains("source_speeds"));
+        assert!(errors[2].contains("curve_steps for GPU"));
+
+        assert!(config.min_fps < config.max_fps);
+        assert_eq!(config.source_speeds.len(), 1);
+        assert_eq!(config.speed_settings(AnimationSource::Gpu).curve_steps, MAX_SPEED_CURVE_STEPS);
+    }
+
+    #[test]
+    fn test_bad_field_types_keep_the_rest() {
+        let (config, problems) =
+            Config::from_json(r#"{"max_fps": "fast", "min_fps": 3, "show_percentage": 1}"#)
+                .unwrap();
+        assert_eq!(problems.len(), 2);
+        assert!((config.min_fps - 3.0).abs() < f32::EPSILON);
+        assert!((config.max_fps - Config::default().max_fps).abs() < f32::EPSILON);
+
+        assert!(Config::from_json("{ not json").is_err());
+        assert!(Config::from_json("[1, 2]").is_err());
+    }
+
     #[test]
     fn test_config_path_ends_with_expected() {
         let path = Config::config_path();
@@ -14,14 +14,53 @@ pub const CAT_SIZE: u32 = 32;
 
 // === CPU Monitoring Constants ===
 
-/// Number of CPU samples to average for smoothing.
+/// Default number of CPU samples to average for smoothing
+/// (`Config::smoothing_samples`).
 ///
 /// At 500ms sample interval, this is 5 seconds of history.
 pub const CPU_SAMPLE_COUNT: usize = 10;
 
-/// CPU sample interval for systemstat
+/// Default CPU sample interval (time between reads of `/proc/stat`,
+/// `Config::sample_interval_ms`)
 pub const CPU_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
 
+/// Default time between checks for config and theme changes
+/// (`Config::config_check_interval_ms`), used only when the files can't be
+/// watched
+pub const CONFIG_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long config and theme files must be quiet before a burst of file
+/// events is reported as one change
+pub const CONFIG_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Time between checks of which schedule is active.  Schedules have
+/// minute resolution, so this only bounds how late one takes effect.
+pub const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Sensor sample interval (time between reads of CPU frequency and
+/// temperature).  Slower than the CPU sampler: these change gradually and
+/// some hwmon drivers are expensive to read.
+pub const SENSOR_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+// === Config File Constants ===
+
+/// Current config file schema version (`Config::version`).  Bump this and
+/// add a step to `config::MIGRATIONS` when a field is renamed or
+/// restructured, and keep the `#[version]` on `Config` in step with it.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// cosmic-config name the settings are stored under
+/// (`~/.config/cosmic/<CONFIG_ID>/v<CONFIG_VERSION>/`)
+pub const CONFIG_ID: &str = "io.github.reality2_roycdavies.cosmic-runkat";
+
+/// Environment variable naming the applet instance whose config to use,
+/// when `--instance` isn't given.  Passed on to the settings hub, which
+/// runs the settings CLI with it.
+pub const INSTANCE_ENV: &str = "RUNKAT_INSTANCE";
+
+/// Longest allowed instance id
+pub const MAX_INSTANCE_ID_LEN: usize = 64;
+
 // === Config Validation Constants ===
 
 /// Minimum allowed animation FPS
@@ -30,6 +69,24 @@ pub const MIN_FPS: f32 = 1.0;
 /// Maximum allowed animation FPS
 pub const MAX_FPS: f32 = 30.0;
 
+/// Most distinct speeds the stepped speed curve can have
+pub const MAX_SPEED_CURVE_STEPS: u32 = 10;
+
+/// Widest allowed sleep hysteresis band, as a % of the threshold
+pub const MAX_SLEEP_HYSTERESIS_PCT: f32 = 90.0;
+
+/// Longest allowed minimum sleep/wake dwell time in milliseconds
+pub const MAX_SLEEP_DWELL_MS: u64 = 60_000;
+
+/// Shortest allowed sample / config check interval in milliseconds
+pub const MIN_INTERVAL_MS: u64 = 100;
+
+/// Longest allowed sample / config check interval in milliseconds
+pub const MAX_INTERVAL_MS: u64 = 5000;
+
+/// Most samples the CPU usage can be averaged over
+pub const MAX_SMOOTHING_SAMPLES: usize = 60;
+
 // === Popup Sizing Constants ===
 
 /// Popup width in pixels
@@ -55,5 +112,8 @@ pub const BAR_WIDTH: f32 = 140.0;
 /// Height of progress bars in the popup
 pub const BAR_HEIGHT: f32 = 12.0;
 
+/// Disk throughput (bytes/sec) that fills a read or write bar in the popup
+pub const DISK_BAR_FULL_BYTES_PER_SEC: f32 = 1_000_000_000.0;
+
 /// Temperature threshold (C) above which the "HOT" status is shown
 pub const TEMP_HOT_THRESHOLD: f32 = 80.0;
@@ -1,18 +1,39 @@
 //! CPU monitoring module
 //!
-//! Reads CPU usage percentages (both aggregate and per-core) using the
-//! `systemstat` crate, which parses `/proc/stat` on Linux.
+//! Reads CPU usage percentages (both aggregate and per-core) from
+//! `/proc/stat`, broken down by where the time went: user, nice, system,
+//! iowait, irq, softirq, steal and guest.
+//!
+//! ## `/proc/stat` format
+//!
+//! ```text
+//! cpu  4705 356 584 3699176 23060 0 277 0 0 0
+//! cpu0 1393 280 234 924396 5658 0 149 0 0 0
+//! ```
+//!
+//! Cumulative time in clock ticks since boot: user, nice, system, idle,
+//! iowait, irq, softirq, steal, guest, guest_nice.  The kernel already
+//! counts guest time inside user (and guest_nice inside nice), so those two
+//! are subtracted back out to keep every share distinct.
 //!
 //! ## Architecture
 //!
-//! The `systemstat` crate uses **blocking I/O** — it sleeps for a sample
-//! interval, then reads `/proc/stat` again to calculate the delta.  Since
+//! Usage is the difference between two reads of `/proc/stat` one sample
+//! interval apart, so sampling means **sleeping** between reads.  Since
 //! our COSMIC applet runs on an async event loop, we can't block it.
 //!
 //! Solution: a dedicated **OS thread** (`std::thread::spawn`) performs the
 //! blocking reads, then pushes results through a **tokio `watch` channel**.
-//! The applet's async `update()` method can read the latest value from the
-//! channel without blocking.
+//! The applet turns a receiver for that channel into a subscription, so it
+//! gets a message as soon as each new sample exists — never a stale one,
+//! and never the same one twice.
+//!
+//! ## Cgroup scoping
+//!
+//! The monitor can also measure a single cgroup v2 — a systemd slice or
+//! scope, or a container — by reading `usage_usec` from its `cpu.stat`
+//! at the start and end of each sample interval.  This lets the cat follow
+//! one workload rather than the whole machine (compositor included).
 //!
 //! ## Error handling
 //!
@@ -20,13 +41,181 @@
 //! and send 0.0 / empty data rather than crashing.  This keeps the applet
 //! alive even on unusual systems.
 
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
-use systemstat::{Platform, System};
+use std::time::{Duration, Instant};
 use tokio::sync::watch;
 
+/// Cumulative time counters for one `cpu` line of `/proc/stat`, in clock
+/// ticks.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+    pub guest_nice: u64,
+}
+
+impl CpuTimes {
+    /// All elapsed time.  Guest time is left out because the kernel already
+    /// includes it in user / nice.
+    pub fn total(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+}
+
+/// One read of `/proc/stat`.
+#[derive(Clone, Debug, Default)]
+pub struct StatSnapshot {
+    /// The summary `cpu` line
+    pub aggregate: CpuTimes,
+    /// One entry per `cpuN` line, with its CPU number (offline CPUs have
+    /// no line, so numbers can have gaps)
+    pub per_core: Vec<(u32, CpuTimes)>,
+}
+
+/// Parse the `cpu` lines of `/proc/stat`.  Returns `None` if there is no
+/// summary line.
+pub fn parse_proc_stat(content: &str) -> Option<StatSnapshot> {
+    let mut aggregate = None;
+    let mut per_core = Vec::new();
+
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(label) = fields.next().and_then(|l| l.strip_prefix("cpu")) else {
+            continue;
+        };
+
+        // Older kernels have fewer columns; missing ones count as zero
+        let mut values = [0u64; 10];
+        for (slot, field) in values.iter_mut().zip(fields) {
+            *slot = field.parse().unwrap_or(0);
+        }
+        let [user, nice, system, idle, iowait, irq, softirq, steal, guest, guest_nice] = values;
+        let times = CpuTimes {
+            user,
+            nice,
+            system,
+            idle,
+            iowait,
+            irq,
+            softirq,
+            steal,
+            guest,
+            guest_nice,
+        };
+
+        if label.is_empty() {
+            aggregate = Some(times);
+        } else if let Ok(id) = label.parse::<u32>() {
+            per_core.push((id, times));
+        }
+    }
+
+    Some(StatSnapshot { aggregate: aggregate?, per_core })
+}
+
+/// Read `/proc/stat`, logging (and returning `None`) on failure.
+fn read_proc_stat() -> Option<StatSnapshot> {
+    fs::read_to_string("/proc/stat")
+        .map_err(|e| tracing::error!("Failed to read /proc/stat: {}", e))
+        .ok()
+        .and_then(|content| parse_proc_stat(&content))
+}
+
+/// Where a CPU's time went over one sample interval, as percentages of the
+/// interval (0.0 to 100.0 each).  Idle time makes up the rest.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CpuBreakdown {
+    /// Normal-priority user code (excluding guests)
+    pub user: f32,
+    /// Niced user code (excluding niced guests)
+    pub nice: f32,
+    /// Kernel code
+    pub system: f32,
+    /// Waiting for I/O to complete
+    pub iowait: f32,
+    /// Servicing hardware interrupts
+    pub irq: f32,
+    /// Servicing softirqs
+    pub softirq: f32,
+    /// Stolen by the hypervisor to run other VMs
+    pub steal: f32,
+    /// Running virtual machine guests
+    pub guest: f32,
+}
+
+impl CpuBreakdown {
+    /// Compute the breakdown between two readings of the same CPU.
+    pub fn between(previous: &CpuTimes, current: &CpuTimes) -> Self {
+        let total = current.total().saturating_sub(previous.total());
+        if total == 0 {
+            return Self::default();
+        }
+        let share = |now: u64, before: u64| {
+            (now.saturating_sub(before) as f64 / total as f64 * 100.0) as f32
+        };
+
+        let guest = share(current.guest, previous.guest);
+        let guest_nice = share(current.guest_nice, previous.guest_nice);
+        Self {
+            user: (share(current.user, previous.user) - guest).max(0.0),
+            nice: (share(current.nice, previous.nice) - guest_nice).max(0.0),
+            system: share(current.system, previous.system),
+            iowait: share(current.iowait, previous.iowait),
+            irq: share(current.irq, previous.irq),
+            softirq: share(current.softirq, previous.softirq),
+            steal: share(current.steal, previous.steal),
+            guest: guest + guest_nice,
+        }
+    }
+
+    /// Time not spent idle, 0.0 to 100.0.  iowait counts as busy, as it
+    /// did with `systemstat`, so this is the filled length of a stacked bar.
+    pub fn busy(&self) -> f32 {
+        (self.user
+            + self.nice
+            + self.system
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+            + self.guest)
+            .clamp(0.0, 100.0)
+    }
+
+    /// The shares in display order with their names — used to draw
+    /// stacked bars and their legend
+    pub fn parts(&self) -> [(&'static str, f32); 8] {
+        [
+            ("user", self.user),
+            ("nice", self.nice),
+            ("system", self.system),
+            ("irq", self.irq),
+            ("softirq", self.softirq),
+            ("guest", self.guest),
+            ("steal", self.steal),
+            ("iowait", self.iowait),
+        ]
+    }
+}
+
 /// Snapshot of CPU usage at a point in time.
 #[derive(Clone, Debug, Default)]
 pub struct CpuUsage {
@@ -34,6 +223,95 @@ pub struct CpuUsage {
     pub aggregate: f32,
     /// Usage for each individual core (0.0 to 100.0 each)
     pub per_core: Vec<f32>,
+    /// Where the time went across all cores
+    pub breakdown: CpuBreakdown,
+    /// Where the time went on each core, matching `per_core`
+    pub per_core_breakdown: Vec<CpuBreakdown>,
+    /// Usage of the selected cgroup, if one is configured
+    pub cgroup: Option<CgroupUsage>,
+}
+
+impl CpuUsage {
+    /// Compute usage from two `/proc/stat` snapshots.  Cores that only
+    /// appear in `current` (just brought online) report zero.
+    pub fn between(previous: &StatSnapshot, current: &StatSnapshot) -> Self {
+        let breakdown = CpuBreakdown::between(&previous.aggregate, &current.aggregate);
+        let per_core_breakdown: Vec<CpuBreakdown> = current
+            .per_core
+            .iter()
+            .map(|(id, cur)| match previous.per_core.iter().find(|(p, _)| p == id) {
+                Some((_, prev)) => CpuBreakdown::between(prev, cur),
+                None => CpuBreakdown::default(),
+            })
+            .collect();
+
+        Self {
+            aggregate: breakdown.busy(),
+            per_core: per_core_breakdown.iter().map(CpuBreakdown::busy).collect(),
+            breakdown,
+            per_core_breakdown,
+            cgroup: None,
+        }
+    }
+}
+
+/// CPU usage of a single cgroup over one sample interval.
+#[derive(Clone, Debug, Default)]
+pub struct CgroupUsage {
+    /// The cgroup as configured, e.g. `user.slice`
+    pub path: String,
+    /// Share of the whole machine's CPU time (0.0 to 100.0), or `None` if
+    /// the cgroup's `cpu.stat` couldn't be read
+    pub percent: Option<f32>,
+    /// The same figure in CPUs' worth of time, e.g. 2.5 = two and a half
+    /// cores kept busy
+    pub cpus: f32,
+}
+
+/// Root of the cgroup v2 unified hierarchy
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Turn a configured cgroup into a directory path.  Relative paths such as
+/// `user.slice/user-1000.slice` are taken relative to `/sys/fs/cgroup`.
+pub fn cgroup_dir(path: &str) -> PathBuf {
+    let path = path.trim();
+    if path.starts_with(CGROUP_ROOT) {
+        PathBuf::from(path)
+    } else {
+        Path::new(CGROUP_ROOT).join(path.trim_start_matches('/'))
+    }
+}
+
+/// Extract `usage_usec` from the contents of a cgroup's `cpu.stat`.
+pub fn parse_usage_usec(content: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        let (key, value) = line.split_once(' ')?;
+        if key == "usage_usec" {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// Read a cgroup's cumulative CPU time in microseconds.
+fn read_usage_usec(dir: &Path) -> Option<u64> {
+    let path = dir.join("cpu.stat");
+    fs::read_to_string(&path)
+        .map_err(|e| tracing::debug!("Failed to read {}: {}", path.display(), e))
+        .ok()
+        .and_then(|content| parse_usage_usec(&content))
+}
+
+/// Convert a `usage_usec` delta over `elapsed` into `(percent, cpus)`.
+pub fn cgroup_share(used_usec: u64, elapsed: Duration, cpu_count: usize) -> (f32, f32) {
+    let elapsed_usec = elapsed.as_micros() as f64;
+    if elapsed_usec <= 0.0 || cpu_count == 0 {
+        return (0.0, 0.0);
+    }
+    let cpus = used_usec as f64 / elapsed_usec;
+    let percent = (cpus / cpu_count as f64 * 100.0).clamp(0.0, 100.0);
+    (percent as f32, cpus as f32)
 }
 
 /// Monitors CPU usage on a background thread and provides the latest
@@ -42,22 +320,29 @@ pub struct CpuUsage {
 /// ## Usage
 ///
 /// ```ignore
-/// let monitor = CpuMonitor::new();
+/// let mut monitor = CpuMonitor::new();
 /// monitor.start(Duration::from_millis(500));
 ///
-/// // Later, on any thread:
-/// let usage = monitor.current();
-/// println!("CPU: {:.1}%", usage.aggregate);
+/// // Later, in async code:
+/// let mut rx = monitor.subscribe();
+/// while rx.changed().await.is_ok() {
+///     println!("CPU: {:.1}%", rx.borrow_and_update().aggregate);
+/// }
 /// ```
 ///
 /// The monitor is automatically stopped when dropped.
 pub struct CpuMonitor {
     /// Sender side of the watch channel (cloned into the background thread)
     tx: watch::Sender<CpuUsage>,
-    /// Receiver side — call `.borrow()` to get the latest value
+    /// Receiver side — cloned for each subscriber
     rx: watch::Receiver<CpuUsage>,
-    /// Shared flag to tell the background thread to stop
+    /// Shared flag to tell the background thread to stop (replaced with a
+    /// fresh one on restart, so only the old thread sees it set)
     stop_flag: Arc<AtomicBool>,
+    /// Interval the running thread samples at, or None if not started
+    interval: Option<Duration>,
+    /// Cgroup to measure as well as the whole machine (empty for none)
+    cgroup: Arc<Mutex<String>>,
 }
 
 impl CpuMonitor {
@@ -71,68 +356,103 @@ impl CpuMonitor {
             tx,
             rx,
             stop_flag: Arc::new(AtomicBool::new(false)),
+            interval: None,
+            cgroup: Arc::new(Mutex::new(String::new())),
+        }
+    }
+
+    /// Measure a cgroup (e.g. `user.slice`) alongside the whole machine,
+    /// or nothing extra if `path` is empty.  Takes effect from the next
+    /// sample.
+    pub fn set_cgroup(&self, path: &str) {
+        if let Ok(mut cgroup) = self.cgroup.lock() {
+            path.clone_into(&mut cgroup);
         }
     }
 
     /// Spawn a background OS thread that samples CPU usage at the given
     /// interval and sends updates through the watch channel.
-    pub fn start(&self, sample_interval: Duration) {
+    pub fn start(&mut self, sample_interval: Duration) {
+        self.interval = Some(sample_interval);
         let tx = self.tx.clone();
         let stop_flag = self.stop_flag.clone();
+        let cgroup = self.cgroup.clone();
 
         thread::spawn(move || {
-            let sys = System::new();
+            let mut previous = read_proc_stat();
 
             while !stop_flag.load(Ordering::Relaxed) {
-                // Start both measurements simultaneously so they cover
-                // the same time window.  Each returns a "measurement in
-                // progress" handle.
-                let aggregate_measurement = sys.cpu_load_aggregate();
-                let per_core_measurement = sys.cpu_load();
-
-                // Sleep for the sample interval — systemstat needs this
-                // time to measure the CPU usage delta between two reads
-                // of /proc/stat.
+                // Snapshot the cgroup's CPU time over the same window
+                let cgroup_path = cgroup.lock().map(|c| c.clone()).unwrap_or_default();
+                let cgroup_start = (!cgroup_path.is_empty()).then(|| {
+                    let dir = cgroup_dir(&cgroup_path);
+                    let usage = read_usage_usec(&dir);
+                    (dir, usage, Instant::now())
+                });
+
+                // Sleep for the sample interval — usage is the delta
+                // between the previous read of /proc/stat and the next one
                 thread::sleep(sample_interval);
+                if stop_flag.load(Ordering::Relaxed) {
+                    break;
+                }
 
-                // Finish the aggregate measurement: returns idle/user/system %
-                let aggregate = aggregate_measurement
-                    .and_then(|cpu| cpu.done())
-                    .map(|load| (1.0 - load.idle) * 100.0)
-                    .unwrap_or_else(|e| {
-                        tracing::error!("CPU aggregate measurement error: {}", e);
-                        0.0 // fallback: report 0% rather than crashing
-                    });
+                let current = read_proc_stat();
+                let mut usage = match (&previous, &current) {
+                    (Some(previous), Some(current)) => CpuUsage::between(previous, current),
+                    // fallback: report 0% rather than crashing
+                    _ => CpuUsage::default(),
+                };
+                previous = current;
 
-                // Finish the per-core measurement
-                let per_core = per_core_measurement
-                    .and_then(|cpus| cpus.done())
-                    .map(|loads| {
-                        loads.iter().map(|l| (1.0 - l.idle) * 100.0).collect()
-                    })
-                    .unwrap_or_else(|e| {
-                        tracing::error!("CPU per-core measurement error: {}", e);
-                        Vec::new() // fallback: empty list
+                // Finish the cgroup measurement, normalised by core count
+                usage.cgroup = cgroup_start.map(|(dir, before, started)| {
+                    let after = read_usage_usec(&dir);
+                    let cpu_count = if usage.per_core.is_empty() {
+                        thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+                    } else {
+                        usage.per_core.len()
+                    };
+                    let share = before.zip(after).map(|(before, after)| {
+                        cgroup_share(after.saturating_sub(before), started.elapsed(), cpu_count)
                     });
+                    CgroupUsage {
+                        path: cgroup_path,
+                        percent: share.map(|(percent, _)| percent),
+                        cpus: share.map(|(_, cpus)| cpus).unwrap_or(0.0),
+                    }
+                });
 
                 // Send the new data — any number of readers can see it
-                if let Err(e) = tx.send(CpuUsage { aggregate, per_core }) {
+                if let Err(e) = tx.send(usage) {
                     tracing::error!("Failed to send CPU update: {}", e);
                 }
             }
         });
     }
 
+    /// Stop the sampling thread and start a new one at a different
+    /// interval.  Subscribers keep their receivers and simply see samples
+    /// arrive at the new rate.  Does nothing if the interval is unchanged.
+    pub fn set_interval(&mut self, sample_interval: Duration) {
+        if self.interval == Some(sample_interval) {
+            return;
+        }
+        self.stop();
+        self.stop_flag = Arc::new(AtomicBool::new(false));
+        self.start(sample_interval);
+    }
+
     /// Signal the background thread to stop.  It will exit on the next
     /// iteration of its sampling loop.
     pub fn stop(&self) {
         self.stop_flag.store(true, Ordering::Relaxed);
     }
 
-    /// Get the latest CPU usage snapshot.  This never blocks — it just
-    /// reads the most recent value that the background thread has sent.
-    pub fn current(&self) -> CpuUsage {
-        self.rx.borrow().clone()
+    /// Get a receiver that is notified each time the background thread
+    /// sends a new sample.  This never blocks.
+    pub fn subscribe(&self) -> watch::Receiver<CpuUsage> {
+        self.rx.clone()
     }
 }
 
@@ -149,3 +469,148 @@ impl Drop for CpuMonitor {
         self.stop();
     }
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STAT_BEFORE: &str = "\
+cpu  1000 100 500 8000 200 10 40 0 0 0
+cpu0 500 50 250 4000 100 5 20 0 0 0
+cpu1 500 50 250 4000 100 5 20 0 0 0
+intr 123456 0 0
+ctxt 987654
+";
+
+    const STAT_AFTER: &str = "\
+cpu  1300 100 600 8450 300 10 40 50 100 0
+cpu0 800 50 350 4000 100 5 20 25 100 0
+cpu1 500 50 250 4375 200 5 20 25 0 0
+intr 123999 0 0
+ctxt 999999
+";
+
+    #[test]
+    fn test_parse_proc_stat() {
+        let snapshot = parse_proc_stat(STAT_BEFORE).unwrap();
+        assert_eq!(snapshot.aggregate.user, 1000);
+        assert_eq!(snapshot.aggregate.softirq, 40);
+        assert_eq!(snapshot.aggregate.total(), 9850);
+        assert_eq!(snapshot.per_core.len(), 2);
+        assert_eq!(snapshot.per_core[1].0, 1);
+
+        // Old kernels without steal/guest columns still parse
+        let old = parse_proc_stat("cpu 1 2 3 4\n").unwrap();
+        assert_eq!(old.aggregate.idle, 4);
+        assert_eq!(old.aggregate.steal, 0);
+
+        assert!(parse_proc_stat("intr 1 2 3\n").is_none());
+    }
+
+    #[test]
+    fn test_cpu_usage_between() {
+        let before = parse_proc_stat(STAT_BEFORE).unwrap();
+        let after = parse_proc_stat(STAT_AFTER).unwrap();
+        let usage = CpuUsage::between(&before, &after);
+
+        // 1000 ticks elapsed: user 300 (of which 100 guest), system 100,
+        // idle 450, iowait 100, steal 50
+        let b = usage.breakdown;
+        assert!((b.user - 20.0).abs() < 0.01);
+        assert!((b.guest - 10.0).abs() < 0.01);
+        assert!((b.system - 10.0).abs() < 0.01);
+        assert!((b.iowait - 10.0).abs() < 0.01);
+        assert!((b.steal - 5.0).abs() < 0.01);
+        assert!((usage.aggregate - 55.0).abs() < 0.01);
+
+        // cpu0 was busy, cpu1 idle apart from iowait and steal
+        assert_eq!(usage.per_core.len(), 2);
+        assert!((usage.per_core[0] - 100.0).abs() < 0.01);
+        assert!((usage.per_core_breakdown[1].iowait - 20.0).abs() < 0.01);
+        assert!((usage.per_core[1] - 25.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_cpu_breakdown_no_time_elapsed() {
+        let before = parse_proc_stat(STAT_BEFORE).unwrap();
+        let usage = CpuUsage::between(&before, &before);
+        assert_eq!(usage.aggregate, 0.0);
+        assert_eq!(usage.breakdown, CpuBreakdown::default());
+    }
+
+    #[test]
+    fn test_subscribe_notified_per_sample() {
+        let mut monitor = CpuMonitor::new();
+        let mut rx = monitor.subscribe();
+        assert!(!rx.has_changed().unwrap());
+
+        monitor.start(Duration::from_millis(20));
+        thread::sleep(Duration::from_millis(200));
+        assert!(rx.has_changed().unwrap());
+
+        // Once seen, a sample isn't reported again
+        rx.borrow_and_update();
+        monitor.stop();
+        thread::sleep(Duration::from_millis(50));
+        rx.borrow_and_update();
+        assert!(!rx.has_changed().unwrap());
+    }
+
+    #[test]
+    fn test_set_interval_restarts_thread() {
+        let mut monitor = CpuMonitor::new();
+        let mut rx = monitor.subscribe();
+
+        // A slow thread wouldn't have sampled yet; the restarted one has
+        monitor.start(Duration::from_secs(60));
+        monitor.set_interval(Duration::from_millis(20));
+        thread::sleep(Duration::from_millis(200));
+        assert!(rx.has_changed().unwrap());
+        rx.borrow_and_update();
+
+        monitor.stop();
+        thread::sleep(Duration::from_millis(50));
+        rx.borrow_and_update();
+        assert!(!rx.has_changed().unwrap());
+    }
+
+    #[test]
+    fn test_cgroup_dir() {
+        assert_eq!(cgroup_dir("user.slice"), PathBuf::from("/sys/fs/cgroup/user.slice"));
+        assert_eq!(
+            cgroup_dir("/system.slice/docker-abc.scope"),
+            PathBuf::from("/sys/fs/cgroup/system.slice/docker-abc.scope")
+        );
+        assert_eq!(
+            cgroup_dir("/sys/fs/cgroup/machine.slice"),
+            PathBuf::from("/sys/fs/cgroup/machine.slice")
+        );
+    }
+
+    #[test]
+    fn test_parse_usage_usec() {
+        let content = "\
+usage_usec 123456789
+user_usec 100000000
+system_usec 23456789
+nr_periods 0
+";
+        assert_eq!(parse_usage_usec(content), Some(123456789));
+        assert_eq!(parse_usage_usec("user_usec 5\n"), None);
+        assert_eq!(parse_usage_usec(""), None);
+    }
+
+    #[test]
+    fn test_cgroup_share() {
+        // 1.5 s of CPU time in 1 s on an 8-core machine
+        let (percent, cpus) = cgroup_share(1_500_000, Duration::from_secs(1), 8);
+        assert!((cpus - 1.5).abs() < 0.001);
+        assert!((percent - 18.75).abs() < 0.001);
+
+        assert_eq!(cgroup_share(1_000, Duration::ZERO, 8), (0.0, 0.0));
+    }
+}
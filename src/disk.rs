@@ -0,0 +1,246 @@
+//! Disk I/O monitoring module
+//!
+//! Reads per-device counters from `/proc/diskstats` and turns the difference
+//! between two samples into read/write throughput and utilisation.  Linux
+//! only; elsewhere no disks are reported.
+//!
+//! ## `/proc/diskstats` format
+//!
+//! One line per block device:
+//!
+//! ```text
+//!  259  0 nvme0n1 1234 0 56789 100 4321 0 98765 200 0 300 300 ...
+//! ```
+//!
+//! After the major/minor numbers and device name, the fields we use are:
+//! - field 3 — sectors read (always 512-byte sectors, regardless of the
+//!   device's real sector size)
+//! - field 7 — sectors written
+//! - field 10 — milliseconds spent doing I/O.  Its growth rate over wall
+//!   time is the device's `%util`, the same figure `iostat -x` shows.
+//!
+//! Partitions are detected through `/sys/class/block/<name>/partition`,
+//! which only exists for partitions.
+
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+/// Size of a `/proc/diskstats` sector in bytes
+const SECTOR_SIZE: u64 = 512;
+
+/// Cumulative I/O counters for one block device, as read from the kernel.
+#[derive(Clone, Debug)]
+pub struct DiskCounters {
+    /// Device name, e.g. `nvme0n1` or `sda`
+    pub name: String,
+    /// Whether this device is a partition of another device
+    pub is_partition: bool,
+    /// Total sectors read since boot
+    pub sectors_read: u64,
+    /// Total sectors written since boot
+    pub sectors_written: u64,
+    /// Total milliseconds the device has spent doing I/O
+    pub io_ms: u64,
+}
+
+/// Read the current counters for every device in `/proc/diskstats`.
+///
+/// Returns an empty list if the file can't be read.
+pub fn read_counters() -> Vec<DiskCounters> {
+    fs::read_to_string("/proc/diskstats")
+        .map(|content| {
+            let mut counters = parse_counters(&content);
+            for disk in &mut counters {
+                disk.is_partition = is_partition(&disk.name);
+            }
+            counters
+        })
+        .unwrap_or_else(|e| {
+            tracing::debug!("Failed to read /proc/diskstats: {}", e);
+            Vec::new()
+        })
+}
+
+/// Parse the contents of `/proc/diskstats`.  Malformed lines are skipped.
+///
+/// `is_partition` is left `false`; `read_counters()` fills it in from sysfs.
+pub fn parse_counters(content: &str) -> Vec<DiskCounters> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.nth(2)?.to_string();
+            let stats: Vec<u64> = fields.map(|f| f.parse().ok()).collect::<Option<_>>()?;
+            Some(DiskCounters {
+                name,
+                is_partition: false,
+                sectors_read: *stats.get(2)?,
+                sectors_written: *stats.get(6)?,
+                io_ms: *stats.get(9)?,
+            })
+        })
+        .collect()
+}
+
+/// Check sysfs to see whether a block device is a partition.
+fn is_partition(name: &str) -> bool {
+    Path::new("/sys/class/block").join(name).join("partition").exists()
+}
+
+/// Throughput and utilisation of one device over the last sample period.
+#[derive(Clone, Debug, Default)]
+pub struct DiskRate {
+    /// Device name
+    pub name: String,
+    /// Bytes read per second
+    pub read_bytes_per_sec: f64,
+    /// Bytes written per second
+    pub write_bytes_per_sec: f64,
+    /// Percentage of wall time the device was busy (0.0 to 100.0)
+    pub util_percent: f32,
+}
+
+/// Disk activity across all selected devices.
+#[derive(Clone, Debug, Default)]
+pub struct DiskUsage {
+    /// One entry per selected device, in `/proc/diskstats` order
+    pub disks: Vec<DiskRate>,
+}
+
+impl DiskUsage {
+    /// Utilisation of the busiest device.
+    ///
+    /// This is the metric used to drive the animation speed in disk mode —
+    /// a single thrashing disk is what makes the machine feel slow, even if
+    /// the others are idle.
+    pub fn max_util(&self) -> f32 {
+        self.disks.iter().map(|d| d.util_percent).fold(0.0f32, f32::max)
+    }
+}
+
+/// Turns successive `/proc/diskstats` readings into per-second rates.
+///
+/// The first call to `sample()` has nothing to compare against and returns
+/// zero rates; every later call reports the average since the previous one.
+#[derive(Default)]
+pub struct DiskSampler {
+    /// Counters and timestamp of the previous sample
+    previous: Option<(Vec<DiskCounters>, Instant)>,
+}
+
+impl DiskSampler {
+    /// Create a sampler with no history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read fresh counters and return the activity of every device that
+    /// `selected(name, is_partition)` accepts.
+    pub fn sample(&mut self, selected: impl Fn(&str, bool) -> bool) -> DiskUsage {
+        let now = Instant::now();
+        let current: Vec<DiskCounters> = read_counters()
+            .into_iter()
+            .filter(|d| selected(&d.name, d.is_partition))
+            .collect();
+
+        let usage = match &self.previous {
+            Some((previous, then)) => {
+                rates_between(previous, &current, now.duration_since(*then).as_secs_f64())
+            }
+            None => rates_between(&current, &current, 0.0),
+        };
+
+        self.previous = Some((current, now));
+        usage
+    }
+}
+
+/// Compute per-device rates from two counter snapshots taken `elapsed`
+/// seconds apart.
+///
+/// Devices that only appear in `current` report zero until the next sample,
+/// as do counters that went backwards.
+pub fn rates_between(previous: &[DiskCounters], current: &[DiskCounters], elapsed: f64) -> DiskUsage {
+    let per_sec = |now: u64, before: u64| {
+        if elapsed > 0.0 {
+            now.saturating_sub(before) as f64 / elapsed
+        } else {
+            0.0
+        }
+    };
+
+    let disks = current
+        .iter()
+        .map(|cur| match previous.iter().find(|p| p.name == cur.name) {
+            Some(prev) => DiskRate {
+                name: cur.name.clone(),
+                read_bytes_per_sec: per_sec(cur.sectors_read, prev.sectors_read)
+                    * SECTOR_SIZE as f64,
+                write_bytes_per_sec: per_sec(cur.sectors_written, prev.sectors_written)
+                    * SECTOR_SIZE as f64,
+                // io_ms per second of wall time / 1000 ms -> fraction busy
+                util_percent: (per_sec(cur.io_ms, prev.io_ms) / 10.0).clamp(0.0, 100.0) as f32,
+            },
+            None => DiskRate { name: cur.name.clone(), ..Default::default() },
+        })
+        .collect();
+
+    DiskUsage { disks }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+   7       0 loop0 50 0 800 10 0 0 0 0 0 20 10 0 0 0 0 0 0
+ 259       0 nvme0n1 1000 0 20000 500 2000 0 40000 800 0 1000 1300 0 0 0 0 0 0
+ 259       1 nvme0n1p1 900 0 18000 450 1900 0 38000 760 0 950 1210 0 0 0 0 0 0
+";
+
+    #[test]
+    fn test_parse_counters() {
+        let counters = parse_counters(SAMPLE);
+        assert_eq!(counters.len(), 3);
+        assert_eq!(counters[1].name, "nvme0n1");
+        assert_eq!(counters[1].sectors_read, 20_000);
+        assert_eq!(counters[1].sectors_written, 40_000);
+        assert_eq!(counters[1].io_ms, 1_000);
+    }
+
+    #[test]
+    fn test_rates_between() {
+        let previous = parse_counters(SAMPLE);
+        let mut current = previous.clone();
+        // One second later: 2048 sectors read (1 MiB), 500 ms busy
+        current[1].sectors_read += 2048;
+        current[1].io_ms += 500;
+
+        let usage = rates_between(&previous, &current, 1.0);
+        let nvme = &usage.disks[1];
+        assert!((nvme.read_bytes_per_sec - 1_048_576.0).abs() < 0.1);
+        assert_eq!(nvme.write_bytes_per_sec, 0.0);
+        assert!((nvme.util_percent - 50.0).abs() < 0.01);
+        assert!((usage.max_util() - 50.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_util_clamped() {
+        let previous = parse_counters(SAMPLE);
+        let mut current = previous.clone();
+        // Counters can briefly run ahead of wall time; never report >100%
+        current[1].io_ms += 1500;
+        let usage = rates_between(&previous, &current, 1.0);
+        assert!((usage.disks[1].util_percent - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_max_util_empty() {
+        assert_eq!(DiskUsage::default().max_util(), 0.0);
+    }
+}
@@ -23,9 +23,15 @@ pub enum RunkatError {
     #[error("Configuration error: {0}")]
     Config(String),
 
-    /// A config value is outside its valid range
-    #[error("Invalid configuration: {0}")]
-    ConfigValidation(String),
+    /// A config field is the wrong type or outside its valid range
+    #[error("Invalid configuration: {field} {message}")]
+    ConfigValidation {
+        /// Name of the field in config.json, e.g. `max_fps` or
+        /// `source_speeds.temperature.min_fps`
+        field: String,
+        /// What is wrong with it, e.g. "must be between 1 and 30, got 50"
+        message: String,
+    },
 
     /// Failed to detect or parse the COSMIC desktop theme
     #[error("Theme error: {0}")]
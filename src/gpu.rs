@@ -0,0 +1,199 @@
+//! GPU monitoring module
+//!
+//! Reads GPU load, VRAM usage and temperature from the DRM subsystem in
+//! sysfs.  What's available depends on the kernel driver:
+//!
+//! - **`amdgpu`** exposes everything below
+//! - **`i915`** / **`xe`** expose a hwmon temperature on recent kernels but
+//!   usually no busy percentage or VRAM figures
+//!
+//! Readings a driver doesn't provide are `None`, and cards that expose
+//! nothing at all (e.g. `simpledrm`) are skipped.
+//!
+//! ## sysfs paths used
+//!
+//! - `/sys/class/drm/card{N}/device/gpu_busy_percent` — load, 0-100
+//! - `/sys/class/drm/card{N}/device/mem_info_vram_used` — bytes
+//! - `/sys/class/drm/card{N}/device/mem_info_vram_total` — bytes
+//! - `/sys/class/drm/card{N}/device/hwmon/hwmon{M}/temp1_input` —
+//!   millidegrees C
+//! - `/sys/class/drm/card{N}/device/driver` — symlink to the driver
+
+use std::fs;
+use std::path::Path;
+
+/// Readings for a single GPU.
+#[derive(Clone, Debug, Default)]
+pub struct GpuCard {
+    /// DRM card name, e.g. `card0`
+    pub name: String,
+    /// Kernel driver name, e.g. `amdgpu` (empty if unknown)
+    pub driver: String,
+    /// GPU load in percent (0.0 to 100.0), if the driver exposes it
+    pub busy_percent: Option<f32>,
+    /// VRAM in use in bytes, if the driver exposes it
+    pub vram_used: Option<u64>,
+    /// Total VRAM in bytes, if the driver exposes it
+    pub vram_total: Option<u64>,
+    /// GPU temperature in degrees Celsius, if the driver exposes it
+    pub temperature: Option<f32>,
+}
+
+/// All GPUs found in `/sys/class/drm`.
+#[derive(Clone, Debug, Default)]
+pub struct GpuInfo {
+    /// One entry per card, ordered by card number
+    pub cards: Vec<GpuCard>,
+}
+
+impl GpuInfo {
+    /// Read every GPU from `/sys/class/drm`.
+    pub fn read() -> Self {
+        Self::read_from(Path::new("/sys/class/drm"))
+    }
+
+    /// Read every GPU from a DRM class directory.  Split out from `read()`
+    /// so tests can point it at a fake sysfs tree.
+    pub fn read_from(drm_path: &Path) -> Self {
+        let Ok(entries) = fs::read_dir(drm_path) else {
+            return Self::default();
+        };
+
+        let mut cards: Vec<GpuCard> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                // Only `cardN` — `cardN-DP-1` etc. are connectors, and
+                // `renderD128` is a second node for the same device
+                let is_card = name
+                    .strip_prefix("card")
+                    .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+                if !is_card {
+                    return None;
+                }
+
+                let card = Self::read_card(name, &entry.path().join("device"));
+                let has_data = card.busy_percent.is_some()
+                    || card.vram_total.is_some()
+                    || card.temperature.is_some();
+                has_data.then_some(card)
+            })
+            .collect();
+
+        // read_dir order is arbitrary — sort card2 before card10
+        cards.sort_by_key(|c| {
+            c.name
+                .trim_start_matches("card")
+                .parse::<u32>()
+                .unwrap_or(u32::MAX)
+        });
+        Self { cards }
+    }
+
+    /// Read a single card's `device/` directory.
+    fn read_card(name: String, device: &Path) -> GpuCard {
+        let driver = fs::read_link(device.join("driver"))
+            .ok()
+            .and_then(|target| target.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_default();
+
+        GpuCard {
+            name,
+            driver,
+            busy_percent: read_number(&device.join("gpu_busy_percent")).map(|v| v as f32),
+            vram_used: read_number(&device.join("mem_info_vram_used")),
+            vram_total: read_number(&device.join("mem_info_vram_total")),
+            temperature: Self::read_hwmon_temp(device),
+        }
+    }
+
+    /// Read the first temperature from the card's hwmon directory.
+    fn read_hwmon_temp(device: &Path) -> Option<f32> {
+        fs::read_dir(device.join("hwmon"))
+            .ok()?
+            .flatten()
+            .find_map(|entry| read_number(&entry.path().join("temp1_input")))
+            .map(|md| md as f32 / 1000.0)
+    }
+
+    /// Load of the busiest GPU (0.0 to 100.0).
+    ///
+    /// This is the metric used to drive the animation speed in GPU mode.
+    pub fn max_busy(&self) -> f32 {
+        self.cards
+            .iter()
+            .filter_map(|c| c.busy_percent)
+            .fold(0.0f32, f32::max)
+    }
+}
+
+/// Read an unsigned integer from a sysfs file.
+fn read_number(path: &Path) -> Option<u64> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Build a throwaway `/sys/class/drm` lookalike in the temp directory.
+    fn fake_drm(test_name: &str) -> PathBuf {
+        let base = std::env::temp_dir()
+            .join(format!("cosmic-runkat-{}-{}", test_name, std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+
+        // card1: amdgpu with everything
+        let device = base.join("card1/device");
+        fs::create_dir_all(device.join("hwmon/hwmon5")).unwrap();
+        fs::write(device.join("gpu_busy_percent"), "42\n").unwrap();
+        fs::write(device.join("mem_info_vram_used"), "1073741824\n").unwrap();
+        fs::write(device.join("mem_info_vram_total"), "8589934592\n").unwrap();
+        fs::write(device.join("hwmon/hwmon5/temp1_input"), "55000\n").unwrap();
+        fs::create_dir_all(base.join("drivers/amdgpu")).unwrap();
+        std::os::unix::fs::symlink(base.join("drivers/amdgpu"), device.join("driver")).unwrap();
+
+        // card0: integrated GPU with only a temperature
+        fs::create_dir_all(base.join("card0/device/hwmon/hwmon2")).unwrap();
+        fs::write(base.join("card0/device/hwmon/hwmon2/temp1_input"), "48000\n").unwrap();
+
+        // Connector and an empty card should both be ignored
+        fs::create_dir_all(base.join("card1-DP-1")).unwrap();
+        fs::create_dir_all(base.join("card2/device")).unwrap();
+
+        base
+    }
+
+    #[test]
+    fn test_gpu_read_from() {
+        let base = fake_drm("gpu-read");
+        let gpus = GpuInfo::read_from(&base);
+        fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(gpus.cards.len(), 2);
+        assert_eq!(gpus.cards[0].name, "card0");
+        assert_eq!(gpus.cards[0].busy_percent, None);
+        assert_eq!(gpus.cards[0].temperature, Some(48.0));
+
+        let amd = &gpus.cards[1];
+        assert_eq!(amd.driver, "amdgpu");
+        assert_eq!(amd.busy_percent, Some(42.0));
+        assert_eq!(amd.vram_used, Some(1 << 30));
+        assert_eq!(amd.vram_total, Some(8 << 30));
+        assert_eq!(amd.temperature, Some(55.0));
+        assert!((gpus.max_busy() - 42.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_gpu_read_missing_dir() {
+        let gpus = GpuInfo::read_from(Path::new("/nonexistent/drm"));
+        assert!(gpus.cards.is_empty());
+        assert_eq!(gpus.max_busy(), 0.0);
+    }
+}
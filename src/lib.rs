@@ -1,4 +1,7 @@
 pub mod config;
 pub mod constants;
+pub mod error;
+pub mod schedule;
 pub mod settings_page;
+pub mod smoothing;
 pub mod sysinfo;
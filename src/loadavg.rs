@@ -0,0 +1,156 @@
+//! Load average module
+//!
+//! Reads the 1/5/15-minute load averages and task counts from
+//! `/proc/loadavg`, and the number of online CPUs from
+//! `/sys/devices/system/cpu/online`, so load can be shown relative to the
+//! size of the machine.  A load of 8.0 is idle-ish on a 32-core box but
+//! badly oversubscribed on a 4-core one.
+//!
+//! ## `/proc/loadavg` format
+//!
+//! ```text
+//! 0.52 0.58 0.59 2/1234 56789
+//! ```
+//!
+//! The three load averages, then `runnable/total` tasks, then the most
+//! recently assigned PID (unused).
+
+use std::fs;
+
+/// Load averages and task counts at a point in time.
+#[derive(Clone, Debug, Default)]
+pub struct LoadAverage {
+    /// Load average over the last minute
+    pub one: f32,
+    /// Load average over the last 5 minutes
+    pub five: f32,
+    /// Load average over the last 15 minutes
+    pub fifteen: f32,
+    /// Tasks currently runnable (running or waiting for a CPU)
+    pub runnable: u32,
+    /// Total number of tasks
+    pub total_tasks: u32,
+    /// Number of online CPUs the averages are normalised by
+    pub online_cpus: u32,
+}
+
+impl LoadAverage {
+    /// Read `/proc/loadavg` and the online CPU count.
+    ///
+    /// Returns all-zero data if `/proc/loadavg` can't be read.
+    pub fn read() -> Self {
+        let online_cpus = fs::read_to_string("/sys/devices/system/cpu/online")
+            .ok()
+            .and_then(|s| parse_cpu_list(&s))
+            .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get() as u32))
+            .unwrap_or(1);
+
+        fs::read_to_string("/proc/loadavg")
+            .map_err(|e| tracing::debug!("Failed to read /proc/loadavg: {}", e))
+            .ok()
+            .and_then(|content| Self::parse(&content, online_cpus))
+            .unwrap_or_default()
+    }
+
+    /// Parse the contents of `/proc/loadavg`.  Returns `None` if the line
+    /// is malformed.
+    pub fn parse(content: &str, online_cpus: u32) -> Option<Self> {
+        let mut fields = content.split_whitespace();
+        let one = fields.next()?.parse().ok()?;
+        let five = fields.next()?.parse().ok()?;
+        let fifteen = fields.next()?.parse().ok()?;
+        let (runnable, total) = fields.next()?.split_once('/')?;
+
+        Some(Self {
+            one,
+            five,
+            fifteen,
+            runnable: runnable.parse().ok()?,
+            total_tasks: total.parse().ok()?,
+            online_cpus,
+        })
+    }
+
+    /// A load average divided by the number of online CPUs, as a
+    /// percentage.  100% means one runnable task per CPU; values above
+    /// 100% mean tasks are queueing.
+    pub fn per_cpu_percentage(&self, load: f32) -> f32 {
+        if self.online_cpus == 0 {
+            return 0.0;
+        }
+        load / self.online_cpus as f32 * 100.0
+    }
+
+    /// The 1-minute load per CPU, clamped to 0-100%.
+    ///
+    /// This is the metric used to drive the animation speed in load mode.
+    pub fn percentage(&self) -> f32 {
+        self.per_cpu_percentage(self.one).clamp(0.0, 100.0)
+    }
+}
+
+/// Count the CPUs in a sysfs CPU list such as `"0-3,8,10-11"` (which is 7).
+pub fn parse_cpu_list(list: &str) -> Option<u32> {
+    let list = list.trim();
+    if list.is_empty() {
+        return None;
+    }
+
+    list.split(',')
+        .map(|range| match range.split_once('-') {
+            Some((start, end)) => {
+                let start: u32 = start.parse().ok()?;
+                let end: u32 = end.parse().ok()?;
+                end.checked_sub(start).map(|n| n + 1)
+            }
+            None => range.parse::<u32>().ok().map(|_| 1),
+        })
+        .sum()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loadavg_parse() {
+        let load = LoadAverage::parse("2.00 1.50 0.75 3/1234 56789\n", 4).unwrap();
+        assert!((load.one - 2.0).abs() < f32::EPSILON);
+        assert!((load.five - 1.5).abs() < f32::EPSILON);
+        assert!((load.fifteen - 0.75).abs() < f32::EPSILON);
+        assert_eq!(load.runnable, 3);
+        assert_eq!(load.total_tasks, 1234);
+    }
+
+    #[test]
+    fn test_loadavg_parse_invalid() {
+        assert!(LoadAverage::parse("", 4).is_none());
+        assert!(LoadAverage::parse("1.0 2.0 3.0 garbage", 4).is_none());
+    }
+
+    #[test]
+    fn test_loadavg_percentage() {
+        // Load 2.0 on 4 CPUs = 50%
+        let load = LoadAverage::parse("2.00 1.50 0.75 3/1234 56789", 4).unwrap();
+        assert!((load.percentage() - 50.0).abs() < 0.01);
+
+        // Load 12.0 on 4 CPUs is oversubscribed: 300% per CPU, clamped to
+        // 100% for the animation
+        let load = LoadAverage::parse("12.00 1.50 0.75 3/1234 56789", 4).unwrap();
+        assert!((load.per_cpu_percentage(load.one) - 300.0).abs() < 0.01);
+        assert!((load.percentage() - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-15\n"), Some(16));
+        assert_eq!(parse_cpu_list("0"), Some(1));
+        assert_eq!(parse_cpu_list("0-3,8,10-11"), Some(7));
+        assert_eq!(parse_cpu_list(""), None);
+        assert_eq!(parse_cpu_list("3-1"), None);
+    }
+}
@@ -9,22 +9,42 @@
 //! - **`--settings`** or **`-s`**: Opens the standalone settings window
 //! - **`--help`**: Shows usage information
 //! - **`--version`**: Shows the version number
+//!
+//! Any of these can be combined with **`--instance <id>`**, anywhere on the
+//! command line, to use the config of another applet instance (or set
+//! `RUNKAT_INSTANCE`), so several cats can follow different metrics.
 
 // Each `mod` declaration tells Rust to include the corresponding source file.
 // For example, `mod applet` includes `src/applet.rs`.
 mod applet;
+mod command;
 mod config;
 mod constants;
 mod cpu;
+mod disk;
 mod error;
+mod gpu;
+mod loadavg;
+mod memory;
+mod metrics;
+mod network;
+mod power;
+mod pressure;
+mod schedule;
 mod settings;
 mod settings_cli;
 mod settings_page;
+mod sleep;
+mod smoothing;
 mod sysinfo;
 mod theme;
+mod watcher;
 
 use std::env;
 
+use config::Config;
+use constants::INSTANCE_ENV;
+
 const APPLET_ID: &str = "io.github.reality2_roycdavies.cosmic-runkat";
 
 /// Print command-line usage information
@@ -32,9 +52,11 @@ fn print_help() {
     println!(
         r#"cosmic-runkat - A cute running cat CPU indicator for COSMIC desktop
 
-Usage: cosmic-runkat [OPTIONS]
+Usage: cosmic-runkat [OPTIONS] [--instance <id>]
 
 Options:
+    --instance <id>          Use the config of applet instance <id> (letters,
+                             digits, '-' and '_'); also read from RUNKAT_INSTANCE
     -s, --settings           Open settings (via hub or standalone)
     --settings-standalone    Open standalone settings window
     -h, --help               Show this help message
@@ -52,7 +74,23 @@ fn print_version() {
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Parse command-line arguments
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // Pick the applet instance before anything loads its config
+    let instance = match take_instance_arg(&mut args) {
+        Some(Some(id)) => Some(id),
+        Some(None) => {
+            eprintln!("Usage: cosmic-runkat [OPTIONS] --instance <id>");
+            std::process::exit(1);
+        }
+        None => env::var(INSTANCE_ENV).ok().filter(|id| !id.is_empty()),
+    };
+    if let Some(id) = instance {
+        if let Err(e) = Config::set_instance(&id) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
 
     // Handle settings CLI protocol commands before initializing tracing,
     // since tracing writes to stdout and would corrupt the JSON output.
@@ -72,7 +110,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             "--settings-action" => {
                 if args.len() < 3 {
-                    eprintln!("Usage: cosmic-runkat --settings-action <action_id>");
+                    eprintln!(
+                        "Usage: cosmic-runkat --settings-action <action_id>\n\
+                         (e.g. reset, switch-profile:<name>)"
+                    );
                     std::process::exit(1);
                 }
                 settings_cli::action(&args[2]);
@@ -93,6 +134,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .init();
 
     tracing::info!("Starting cosmic-runkat v{}", env!("CARGO_PKG_VERSION"));
+    if let Some(id) = Config::instance() {
+        tracing::info!("Using the config of instance {}", id);
+    }
 
     if args.len() > 1 {
         match args[1].as_str() {
@@ -123,11 +167,34 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 }
 
+/// Remove `--instance <id>` (or `--instance=<id>`) from wherever it is on
+/// the command line, so the other options keep their positions.  Returns
+/// `None` if it isn't there and `Some(None)` if the id is missing.
+fn take_instance_arg(args: &mut Vec<String>) -> Option<Option<String>> {
+    let index = args
+        .iter()
+        .skip(1)
+        .position(|arg| arg == "--instance" || arg.starts_with("--instance="))?
+        + 1;
+    let arg = args.remove(index);
+    if let Some(id) = arg.strip_prefix("--instance=") {
+        return Some(Some(id.to_string()));
+    }
+    if index < args.len() {
+        Some(Some(args.remove(index)))
+    } else {
+        Some(None)
+    }
+}
+
 /// Try to open settings via cosmic-applet-settings hub; fall back to standalone.
 fn open_settings() -> cosmic::iced::Result {
     use std::process::Command;
+    // The hub runs the settings CLI, which reads the instance from the
+    // environment
     if Command::new("cosmic-applet-settings")
         .arg(APPLET_ID)
+        .env(INSTANCE_ENV, Config::instance().unwrap_or_default())
         .spawn()
         .is_ok()
     {
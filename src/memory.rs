@@ -0,0 +1,159 @@
+//! Memory monitoring module
+//!
+//! Reads RAM and swap statistics from `/proc/meminfo` on Linux.  On other
+//! platforms (or if the file can't be read) all values are zero and the
+//! applet simply reports 0% memory usage.
+//!
+//! ## Fields used
+//!
+//! - `MemTotal` — total usable RAM
+//! - `MemAvailable` — RAM that can be handed out without swapping.  "Used"
+//!   memory is `MemTotal - MemAvailable`, which matches what `free` and most
+//!   desktop system monitors report.
+//! - `Cached` / `Buffers` — page cache and block device buffers (reclaimable)
+//! - `SwapTotal` / `SwapFree` — swap space
+
+use std::fs;
+
+/// Snapshot of system memory usage.  All values are in KiB, exactly as
+/// reported by the kernel.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryInfo {
+    /// Total usable RAM in KiB
+    pub total_kb: u64,
+    /// RAM available for new allocations without swapping, in KiB
+    pub available_kb: u64,
+    /// Page cache in KiB
+    pub cached_kb: u64,
+    /// Block device buffers in KiB
+    pub buffers_kb: u64,
+    /// Total swap space in KiB
+    pub swap_total_kb: u64,
+    /// Unused swap space in KiB
+    pub swap_free_kb: u64,
+}
+
+impl MemoryInfo {
+    /// Read the current memory statistics from `/proc/meminfo`.
+    ///
+    /// Returns all-zero data if the file can't be read.
+    pub fn read() -> Self {
+        fs::read_to_string("/proc/meminfo")
+            .map(|content| Self::parse(&content))
+            .unwrap_or_else(|e| {
+                tracing::debug!("Failed to read /proc/meminfo: {}", e);
+                Self::default()
+            })
+    }
+
+    /// Parse the contents of `/proc/meminfo`.
+    ///
+    /// Each line looks like `MemTotal:       32614284 kB`.  Unknown keys are
+    /// ignored, and missing keys stay at zero.
+    pub fn parse(content: &str) -> Self {
+        let mut info = Self::default();
+
+        for line in content.lines() {
+            let Some((key, rest)) = line.split_once(':') else {
+                continue;
+            };
+            let Some(value) = rest
+                .split_whitespace()
+                .next()
+                .and_then(|v| v.parse::<u64>().ok())
+            else {
+                continue;
+            };
+
+            match key {
+                "MemTotal" => info.total_kb = value,
+                "MemAvailable" => info.available_kb = value,
+                "Cached" => info.cached_kb = value,
+                "Buffers" => info.buffers_kb = value,
+                "SwapTotal" => info.swap_total_kb = value,
+                "SwapFree" => info.swap_free_kb = value,
+                _ => {}
+            }
+        }
+
+        info
+    }
+
+    /// RAM in use (not available for new allocations), in KiB
+    pub fn used_kb(&self) -> u64 {
+        self.total_kb.saturating_sub(self.available_kb)
+    }
+
+    /// Swap in use, in KiB
+    pub fn swap_used_kb(&self) -> u64 {
+        self.swap_total_kb.saturating_sub(self.swap_free_kb)
+    }
+
+    /// Used RAM as a percentage of total RAM (0.0 to 100.0).
+    ///
+    /// This is the metric used to drive the animation speed in memory mode.
+    pub fn percentage(&self) -> f32 {
+        if self.total_kb == 0 {
+            return 0.0;
+        }
+        (self.used_kb() as f32 / self.total_kb as f32 * 100.0).clamp(0.0, 100.0)
+    }
+}
+
+/// Convert a KiB value into GiB for display (e.g. `"12.3 GiB"`).
+pub fn kb_to_gib(kb: u64) -> f32 {
+    kb as f32 / (1024.0 * 1024.0)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+MemTotal:       16000000 kB
+MemFree:         2000000 kB
+MemAvailable:    4000000 kB
+Buffers:          500000 kB
+Cached:          3000000 kB
+SwapCached:            0 kB
+SwapTotal:       8000000 kB
+SwapFree:        6000000 kB
+";
+
+    #[test]
+    fn test_memory_parse() {
+        let mem = MemoryInfo::parse(SAMPLE);
+        assert_eq!(mem.total_kb, 16_000_000);
+        assert_eq!(mem.available_kb, 4_000_000);
+        assert_eq!(mem.buffers_kb, 500_000);
+        assert_eq!(mem.cached_kb, 3_000_000);
+        assert_eq!(mem.swap_used_kb(), 2_000_000);
+    }
+
+    #[test]
+    fn test_memory_percentage() {
+        let mem = MemoryInfo::parse(SAMPLE);
+        // 12 GB used out of 16 GB = 75%
+        assert_eq!(mem.used_kb(), 12_000_000);
+        assert!((mem.percentage() - 75.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_memory_percentage_empty() {
+        // Edge case: nothing could be read
+        let mem = MemoryInfo::default();
+        assert_eq!(mem.percentage(), 0.0);
+    }
+
+    #[test]
+    fn test_memory_read() {
+        let mem = MemoryInfo::read();
+        // /proc/meminfo may be missing on non-Linux systems, but the call
+        // should never panic and values should be consistent
+        assert!(mem.available_kb <= mem.total_kb || mem.total_kb == 0);
+    }
+}
@@ -0,0 +1,232 @@
+//! Background metrics sampling module
+//!
+//! Reads the memory, network, disk I/O, GPU, pressure, load average and
+//! power metrics on a dedicated **OS thread**, like `CpuMonitor` and
+//! `SensorMonitor`, so none of their procfs / sysfs reads run on the
+//! applet's event loop.  Scanning `/sys/class/drm` or an EC-backed hwmon
+//! can take tens of milliseconds, which would otherwise stall the
+//! animation on every sample.
+//!
+//! Only the metrics that drive the cat (the animation source, or the
+//! sources weighted in the mix) are read; the rest stay at their defaults.
+//! The popup shows the same source, so it never needs more.
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::config::{disk_selected, interface_selected};
+use crate::disk::{DiskSampler, DiskUsage};
+use crate::gpu::GpuInfo;
+use crate::loadavg::LoadAverage;
+use crate::memory::MemoryInfo;
+use crate::network::{NetworkSampler, NetworkUsage};
+use crate::power::{PowerSampler, PowerUsage};
+use crate::pressure::PressureInfo;
+
+/// Which metrics to read, how to filter them and how often.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MetricSettings {
+    pub memory: bool,
+    pub network: bool,
+    pub disk: bool,
+    pub gpu: bool,
+    pub pressure: bool,
+    pub load_average: bool,
+    pub power: bool,
+    /// `Config::network_include` patterns
+    pub network_include: Vec<String>,
+    /// `Config::network_exclude` patterns
+    pub network_exclude: Vec<String>,
+    /// `Config::disk_exclude` patterns
+    pub disk_exclude: Vec<String>,
+    /// `Config::disk_include_partitions`
+    pub disk_include_partitions: bool,
+    /// Time between samples
+    pub interval: Duration,
+}
+
+impl MetricSettings {
+    /// Whether any metric is wanted at all
+    pub fn any(&self) -> bool {
+        self.memory
+            || self.network
+            || self.disk
+            || self.gpu
+            || self.pressure
+            || self.load_average
+            || self.power
+    }
+}
+
+/// One reading of every wanted metric.  Metrics that weren't wanted are
+/// left at their defaults.
+#[derive(Clone, Debug, Default)]
+pub struct MetricsSample {
+    pub memory: MemoryInfo,
+    pub network: NetworkUsage,
+    pub disk: DiskUsage,
+    pub gpu: GpuInfo,
+    pub pressure: PressureInfo,
+    pub load_average: LoadAverage,
+    pub power: PowerUsage,
+}
+
+/// Keeps the counters that rates are computed from between samples.
+#[derive(Default)]
+struct Samplers {
+    network: NetworkSampler,
+    disk: DiskSampler,
+    power: PowerSampler,
+}
+
+impl Samplers {
+    /// Read every metric `settings` asks for.  The counters of unwanted
+    /// rate metrics are dropped, so a metric that comes back into use
+    /// doesn't report a rate averaged over the time it was off.
+    fn sample(&mut self, settings: &MetricSettings) -> MetricsSample {
+        let mut sample = MetricsSample::default();
+
+        if settings.memory {
+            sample.memory = MemoryInfo::read();
+        }
+        if settings.network {
+            sample.network = self.network.sample(|name| {
+                interface_selected(&settings.network_include, &settings.network_exclude, name)
+            });
+        } else {
+            self.network = NetworkSampler::new();
+        }
+        if settings.disk {
+            sample.disk = self.disk.sample(|name, is_partition| {
+                disk_selected(
+                    &settings.disk_exclude,
+                    settings.disk_include_partitions,
+                    name,
+                    is_partition,
+                )
+            });
+        } else {
+            self.disk = DiskSampler::new();
+        }
+        if settings.gpu {
+            sample.gpu = GpuInfo::read();
+        }
+        if settings.pressure {
+            sample.pressure = PressureInfo::read();
+        }
+        if settings.load_average {
+            sample.load_average = LoadAverage::read();
+        }
+        if settings.power {
+            sample.power = self.power.sample();
+        } else {
+            self.power = PowerSampler::new();
+        }
+
+        sample
+    }
+}
+
+/// Reads the wanted metrics on a background thread and provides each new
+/// sample via a `watch` channel.
+///
+/// Nothing is read until `configure()` asks for at least one metric, like
+/// `CommandRunner`; then `subscribe()` to be notified of new samples.  The
+/// thread is stopped when the monitor is dropped.
+pub struct MetricsMonitor {
+    /// Receiver side — cloned for each subscriber
+    rx: watch::Receiver<MetricsSample>,
+    /// New settings for the thread, which waits on them between samples.
+    /// Dropping it stops the thread.
+    settings_tx: mpsc::Sender<MetricSettings>,
+    /// The settings last sent, so unchanged ones aren't sent again
+    settings: Mutex<MetricSettings>,
+}
+
+impl MetricsMonitor {
+    /// Create a monitor and start its (initially idle) background thread.
+    pub fn new() -> Self {
+        let (tx, rx) = watch::channel(MetricsSample::default());
+        let (settings_tx, settings_rx) = mpsc::channel::<MetricSettings>();
+
+        thread::spawn(move || {
+            let mut settings = MetricSettings::default();
+            let mut samplers = Samplers::default();
+            loop {
+                if settings.any() && tx.send(samplers.sample(&settings)).is_err() {
+                    break; // monitor dropped
+                }
+
+                // Wait for the next sample, waking early if the settings
+                // change (and only for that when nothing is wanted)
+                let next = if settings.any() {
+                    settings_rx.recv_timeout(settings.interval)
+                } else {
+                    settings_rx.recv().map_err(RecvTimeoutError::from)
+                };
+                match next {
+                    Ok(new) => settings = new,
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break, // monitor dropped
+                }
+            }
+        });
+
+        Self { rx, settings_tx, settings: Mutex::new(MetricSettings::default()) }
+    }
+
+    /// Change what the monitor reads.  Does nothing if the settings are
+    /// unchanged, so it's cheap to call on every config reload.
+    pub fn configure(&self, settings: MetricSettings) {
+        let mut current = self.settings.lock().unwrap_or_else(PoisonError::into_inner);
+        if *current == settings {
+            return;
+        }
+        *current = settings.clone();
+        let _ = self.settings_tx.send(settings);
+    }
+
+    /// Get a receiver that is notified each time the background thread
+    /// sends a new sample.  This never blocks.
+    pub fn subscribe(&self) -> watch::Receiver<MetricsSample> {
+        self.rx.clone()
+    }
+}
+
+impl Default for MetricsMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_until_configured() {
+        let monitor = MetricsMonitor::new();
+        let mut rx = monitor.subscribe();
+        thread::sleep(Duration::from_millis(200));
+        assert!(!rx.has_changed().unwrap());
+
+        monitor.configure(MetricSettings {
+            memory: true,
+            interval: Duration::from_secs(60),
+            ..Default::default()
+        });
+        thread::sleep(Duration::from_millis(200));
+        // The first sample is sent straight away, with only memory read
+        assert!(rx.has_changed().unwrap());
+        let sample = rx.borrow_and_update().clone();
+        assert!(sample.memory.total_kb > 0);
+        assert!(sample.gpu.cards.is_empty());
+    }
+}
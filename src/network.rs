@@ -0,0 +1,278 @@
+//! Network throughput module
+//!
+//! Reads per-interface byte counters from `/proc/net/dev` and turns the
+//! difference between two samples into receive/transmit rates.  Like the
+//! sysinfo readers this is Linux-specific; elsewhere no interfaces are
+//! reported and the cat simply sleeps.
+//!
+//! ## `/proc/net/dev` format
+//!
+//! Two header lines, then one line per interface:
+//!
+//! ```text
+//!   eth0: 123456 789 0 0 0 0 0 0 654321 456 0 0 0 0 0 0
+//! ```
+//!
+//! The first number after the colon is received bytes, the ninth is
+//! transmitted bytes.  Both are cumulative since boot.
+
+use std::fs;
+use std::time::Instant;
+
+/// Cumulative byte counters for one interface, as read from the kernel.
+#[derive(Clone, Debug)]
+pub struct InterfaceCounters {
+    /// Interface name, e.g. `eth0` or `wlp2s0`
+    pub name: String,
+    /// Total bytes received since boot
+    pub rx_bytes: u64,
+    /// Total bytes transmitted since boot
+    pub tx_bytes: u64,
+}
+
+/// Read the current counters for every interface in `/proc/net/dev`.
+///
+/// Returns an empty list if the file can't be read.
+pub fn read_counters() -> Vec<InterfaceCounters> {
+    fs::read_to_string("/proc/net/dev")
+        .map(|content| parse_counters(&content))
+        .unwrap_or_else(|e| {
+            tracing::debug!("Failed to read /proc/net/dev: {}", e);
+            Vec::new()
+        })
+}
+
+/// Parse the contents of `/proc/net/dev`.  Malformed lines are skipped.
+pub fn parse_counters(content: &str) -> Vec<InterfaceCounters> {
+    content
+        .lines()
+        .filter_map(|line| {
+            // Header lines have no colon (or a `|` before it) — skip them
+            let (name, rest) = line.split_once(':')?;
+            let fields: Vec<u64> = rest
+                .split_whitespace()
+                .map(|f| f.parse().ok())
+                .collect::<Option<_>>()?;
+            Some(InterfaceCounters {
+                name: name.trim().to_string(),
+                rx_bytes: *fields.first()?,
+                tx_bytes: *fields.get(8)?,
+            })
+        })
+        .collect()
+}
+
+/// Receive/transmit rate for one interface over the last sample period.
+#[derive(Clone, Debug, Default)]
+pub struct InterfaceRate {
+    /// Interface name
+    pub name: String,
+    /// Bytes received per second
+    pub rx_bytes_per_sec: f64,
+    /// Bytes transmitted per second
+    pub tx_bytes_per_sec: f64,
+}
+
+/// Network throughput across all selected interfaces.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkUsage {
+    /// One entry per selected interface, in `/proc/net/dev` order
+    pub interfaces: Vec<InterfaceRate>,
+}
+
+impl NetworkUsage {
+    /// Combined receive + transmit rate of all interfaces, in bytes/sec
+    pub fn total_bytes_per_sec(&self) -> f64 {
+        self.interfaces
+            .iter()
+            .map(|i| i.rx_bytes_per_sec + i.tx_bytes_per_sec)
+            .sum()
+    }
+
+    /// Combined throughput as a percentage of the configured "full speed"
+    /// bandwidth (in Mbit/s).
+    ///
+    /// This is the metric used to drive the animation speed in network mode.
+    pub fn percentage(&self, full_speed_mbps: f32) -> f32 {
+        let full_speed = mbps_to_bytes_per_sec(full_speed_mbps);
+        if full_speed <= 0.0 {
+            return 0.0;
+        }
+        ((self.total_bytes_per_sec() / full_speed) * 100.0).clamp(0.0, 100.0) as f32
+    }
+}
+
+/// Turns successive `/proc/net/dev` readings into per-second rates.
+///
+/// The first call to `sample()` has nothing to compare against and returns
+/// zero rates; every later call reports the average rate since the
+/// previous one.
+#[derive(Default)]
+pub struct NetworkSampler {
+    /// Counters and timestamp of the previous sample
+    previous: Option<(Vec<InterfaceCounters>, Instant)>,
+}
+
+impl NetworkSampler {
+    /// Create a sampler with no history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read fresh counters and return the rate for every interface that
+    /// `selected` accepts.
+    pub fn sample(&mut self, selected: impl Fn(&str) -> bool) -> NetworkUsage {
+        let now = Instant::now();
+        let current = read_counters();
+
+        let usage = match &self.previous {
+            Some((previous, then)) => {
+                rates_between(previous, &current, now.duration_since(*then).as_secs_f64())
+            }
+            None => NetworkUsage {
+                interfaces: current
+                    .iter()
+                    .map(|c| InterfaceRate { name: c.name.clone(), ..Default::default() })
+                    .collect(),
+            },
+        };
+
+        self.previous = Some((current, now));
+
+        NetworkUsage {
+            interfaces: usage
+                .interfaces
+                .into_iter()
+                .filter(|i| selected(&i.name))
+                .collect(),
+        }
+    }
+}
+
+/// Compute per-interface rates from two counter snapshots taken `elapsed`
+/// seconds apart.
+///
+/// Interfaces that only appear in `current` (e.g. a VPN that just came up)
+/// report zero until the next sample.  Counters that went backwards (the
+/// interface was reset) are also treated as zero.
+pub fn rates_between(
+    previous: &[InterfaceCounters],
+    current: &[InterfaceCounters],
+    elapsed: f64,
+) -> NetworkUsage {
+    let interfaces = current
+        .iter()
+        .map(|cur| {
+            let rate = |now: u64, before: u64| {
+                if elapsed > 0.0 {
+                    now.saturating_sub(before) as f64 / elapsed
+                } else {
+                    0.0
+                }
+            };
+
+            let (rx, tx) = previous
+                .iter()
+                .find(|p| p.name == cur.name)
+                .map(|prev| (rate(cur.rx_bytes, prev.rx_bytes), rate(cur.tx_bytes, prev.tx_bytes)))
+                .unwrap_or((0.0, 0.0));
+
+            InterfaceRate {
+                name: cur.name.clone(),
+                rx_bytes_per_sec: rx,
+                tx_bytes_per_sec: tx,
+            }
+        })
+        .collect();
+
+    NetworkUsage { interfaces }
+}
+
+/// Convert a bandwidth in Mbit/s into bytes per second.
+pub fn mbps_to_bytes_per_sec(mbps: f32) -> f64 {
+    mbps as f64 * 1_000_000.0 / 8.0
+}
+
+/// Format a byte rate for display, e.g. `"512 B/s"`, `"12.3 KB/s"`,
+/// `"1.5 MB/s"`.
+pub fn format_rate(bytes_per_sec: f64) -> String {
+    if bytes_per_sec >= 1_000_000_000.0 {
+        format!("{:.1} GB/s", bytes_per_sec / 1_000_000_000.0)
+    } else if bytes_per_sec >= 1_000_000.0 {
+        format!("{:.1} MB/s", bytes_per_sec / 1_000_000.0)
+    } else if bytes_per_sec >= 1_000.0 {
+        format!("{:.1} KB/s", bytes_per_sec / 1_000.0)
+    } else {
+        format!("{:.0} B/s", bytes_per_sec)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:  500000     100    0    0    0     0          0         0   500000     100    0    0    0     0       0          0
+  eth0: 1000000    2000    0    0    0     0          0         0   200000     300    0    0    0     0       0          0
+";
+
+    #[test]
+    fn test_parse_counters() {
+        let counters = parse_counters(SAMPLE);
+        assert_eq!(counters.len(), 2);
+        assert_eq!(counters[1].name, "eth0");
+        assert_eq!(counters[1].rx_bytes, 1_000_000);
+        assert_eq!(counters[1].tx_bytes, 200_000);
+    }
+
+    #[test]
+    fn test_rates_between() {
+        let previous = parse_counters(SAMPLE);
+        let mut current = previous.clone();
+        current[1].rx_bytes += 2_000_000;
+        current[1].tx_bytes += 500_000;
+
+        // 2 seconds apart: eth0 receives 1 MB/s, transmits 250 KB/s
+        let usage = rates_between(&previous, &current, 2.0);
+        assert!((usage.interfaces[1].rx_bytes_per_sec - 1_000_000.0).abs() < 0.1);
+        assert!((usage.interfaces[1].tx_bytes_per_sec - 250_000.0).abs() < 0.1);
+        assert_eq!(usage.interfaces[0].rx_bytes_per_sec, 0.0);
+        assert!((usage.total_bytes_per_sec() - 1_250_000.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_rates_between_counter_reset() {
+        let previous = parse_counters(SAMPLE);
+        let mut current = previous.clone();
+        current[1].rx_bytes = 0;
+        let usage = rates_between(&previous, &current, 1.0);
+        assert_eq!(usage.interfaces[1].rx_bytes_per_sec, 0.0);
+    }
+
+    #[test]
+    fn test_network_percentage() {
+        let usage = NetworkUsage {
+            interfaces: vec![InterfaceRate {
+                name: "eth0".into(),
+                rx_bytes_per_sec: 6_250_000.0, // 50 Mbit/s
+                tx_bytes_per_sec: 0.0,
+            }],
+        };
+        assert!((usage.percentage(100.0) - 50.0).abs() < 0.01);
+        // Saturated links are clamped to 100%
+        assert!((usage.percentage(10.0) - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(512.0), "512 B/s");
+        assert_eq!(format_rate(12_300.0), "12.3 KB/s");
+        assert_eq!(format_rate(1_500_000.0), "1.5 MB/s");
+    }
+}
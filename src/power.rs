@@ -0,0 +1,406 @@
+//! Power draw monitoring module
+//!
+//! Reads power consumption from two places:
+//!
+//! - **RAPL** (Running Average Power Limit) energy counters in
+//!   `/sys/class/powercap/intel-rapl:*` — Intel, and AMD Zen on recent
+//!   kernels.  Each zone has a cumulative `energy_uj` counter; the
+//!   difference between two samples gives watts.  Zones are `package-N`,
+//!   with `core`, `uncore` and `dram` subzones where the CPU supports them.
+//! - **Batteries** in `/sys/class/power_supply/BAT*` — charge, status, and
+//!   the instantaneous `power_now` (or `current_now` × `voltage_now` on
+//!   batteries that don't report power directly).
+//!
+//! Since CVE-2020-8694 most distributions make `energy_uj` readable by
+//! root only, so on many systems only the battery figures are available.
+//! The animation uses the package power if RAPL can be read, and the
+//! battery discharge rate otherwise.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+/// A RAPL zone's cumulative energy counter, as read from sysfs.
+#[derive(Clone, Debug)]
+pub struct RaplCounter {
+    /// Zone directory name, e.g. `intel-rapl:0:1`
+    pub zone: String,
+    /// Domain name from the zone's `name` file, e.g. `package-0` or `core`
+    pub name: String,
+    /// Energy used since the counter last wrapped, in microjoules
+    pub energy_uj: u64,
+    /// Value at which `energy_uj` wraps back to zero
+    pub max_energy_range_uj: u64,
+}
+
+/// Read the energy counters of every readable RAPL zone under a powercap
+/// directory (normally `/sys/class/powercap`).
+pub fn read_rapl_counters(powercap: &Path) -> Vec<RaplCounter> {
+    let Ok(entries) = fs::read_dir(powercap) else {
+        return Vec::new();
+    };
+
+    let mut counters: Vec<RaplCounter> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let zone = entry.file_name().to_string_lossy().to_string();
+            // `intel-rapl` itself is the control type, not a zone, and the
+            // `intel-rapl-mmio:*` zones duplicate the MSR ones
+            if !zone.starts_with("intel-rapl:") {
+                return None;
+            }
+
+            let path = entry.path();
+            Some(RaplCounter {
+                name: read_string(&path.join("name"))?,
+                energy_uj: read_number(&path.join("energy_uj"))?,
+                max_energy_range_uj: read_number(&path.join("max_energy_range_uj"))
+                    .unwrap_or(0),
+                zone,
+            })
+        })
+        .collect();
+
+    // read_dir order is arbitrary — keep each package next to its subzones
+    counters.sort_by(|a, b| a.zone.cmp(&b.zone));
+    counters
+}
+
+/// Average power of one RAPL domain between two samples.
+#[derive(Clone, Debug, Default)]
+pub struct PowerDomain {
+    /// Zone directory name, e.g. `intel-rapl:0:1`
+    pub zone: String,
+    /// Domain name, e.g. `package-0` or `core`
+    pub name: String,
+    /// Average power draw in watts
+    pub watts: f32,
+}
+
+impl PowerDomain {
+    /// Whether this is a whole-package zone (rather than a subzone)
+    pub fn is_package(&self) -> bool {
+        self.name.starts_with("package")
+    }
+
+    /// Whether this zone sits inside another one (e.g. `core` inside
+    /// `package-0`), judging by its `intel-rapl:N:M` zone name
+    pub fn is_subzone(&self) -> bool {
+        self.zone.matches(':').count() > 1
+    }
+
+    /// Name for display, e.g. `Package 0`, `Core`, `DRAM`
+    pub fn display_name(&self) -> String {
+        match self.name.as_str() {
+            "core" => "Core".to_string(),
+            "uncore" => "Uncore".to_string(),
+            "dram" => "DRAM".to_string(),
+            "psys" => "Platform".to_string(),
+            name => match name.strip_prefix("package-") {
+                Some(n) => format!("Package {}", n),
+                None => name.to_string(),
+            },
+        }
+    }
+}
+
+/// State of one battery.
+#[derive(Clone, Debug, Default)]
+pub struct BatteryInfo {
+    /// Supply name, e.g. `BAT0`
+    pub name: String,
+    /// Charge level in percent, if reported
+    pub capacity: Option<f32>,
+    /// Status string from the kernel: `Charging`, `Discharging`, `Full`, ...
+    pub status: String,
+    /// Charge or discharge rate in watts, if reported
+    pub watts: Option<f32>,
+}
+
+impl BatteryInfo {
+    /// Read every `BAT*` supply under a power_supply directory (normally
+    /// `/sys/class/power_supply`).
+    pub fn read_all(power_supply: &Path) -> Vec<Self> {
+        let Ok(entries) = fs::read_dir(power_supply) else {
+            return Vec::new();
+        };
+
+        let mut batteries: Vec<Self> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !name.starts_with("BAT") {
+                    return None;
+                }
+
+                let path = entry.path();
+                // power_now is in microwatts; otherwise current_now (µA) ×
+                // voltage_now (µV) gives picowatts
+                let watts = read_number(&path.join("power_now"))
+                    .map(|uw| uw as f32 / 1_000_000.0)
+                    .or_else(|| {
+                        let ua = read_number(&path.join("current_now"))?;
+                        let uv = read_number(&path.join("voltage_now"))?;
+                        Some((ua as f64 * uv as f64 / 1e12) as f32)
+                    });
+
+                Some(Self {
+                    name,
+                    capacity: read_number(&path.join("capacity")).map(|c| c as f32),
+                    status: read_string(&path.join("status")).unwrap_or_default(),
+                    watts,
+                })
+            })
+            .collect();
+
+        batteries.sort_by(|a, b| a.name.cmp(&b.name));
+        batteries
+    }
+
+    /// Whether the battery is currently powering the machine
+    pub fn is_discharging(&self) -> bool {
+        self.status == "Discharging"
+    }
+}
+
+/// Power readings for the whole machine.
+#[derive(Clone, Debug, Default)]
+pub struct PowerUsage {
+    /// One entry per readable RAPL zone, packages before their subzones
+    pub domains: Vec<PowerDomain>,
+    /// One entry per battery
+    pub batteries: Vec<BatteryInfo>,
+}
+
+impl PowerUsage {
+    /// Whether any RAPL counter could be read
+    pub fn has_rapl(&self) -> bool {
+        !self.domains.is_empty()
+    }
+
+    /// Total package power in watts, if RAPL is readable
+    pub fn package_watts(&self) -> Option<f32> {
+        let mut packages = self.domains.iter().filter(|d| d.is_package()).peekable();
+        packages.peek()?;
+        Some(packages.map(|d| d.watts).sum())
+    }
+
+    /// Combined discharge rate of all discharging batteries, in watts
+    pub fn discharge_watts(&self) -> f32 {
+        self.batteries
+            .iter()
+            .filter(|b| b.is_discharging())
+            .filter_map(|b| b.watts)
+            .sum()
+    }
+
+    /// The power draw that drives the animation: package power if RAPL is
+    /// readable, otherwise the battery discharge rate (0 on AC power).
+    pub fn watts(&self) -> f32 {
+        self.package_watts().unwrap_or_else(|| self.discharge_watts())
+    }
+
+    /// Power draw as a percentage of `full_watts`, clamped to 0-100%.
+    ///
+    /// This is the metric used to drive the animation speed in power mode.
+    pub fn percentage(&self, full_watts: f32) -> f32 {
+        if full_watts <= 0.0 {
+            return 0.0;
+        }
+        (self.watts() / full_watts * 100.0).clamp(0.0, 100.0)
+    }
+}
+
+/// Turns successive RAPL counter readings into watts.
+///
+/// Keeps the previous sample's counters; the first call after creation
+/// reports 0 W for every domain.
+#[derive(Default)]
+pub struct PowerSampler {
+    /// Counters and timestamp of the previous sample
+    previous: Option<(Vec<RaplCounter>, Instant)>,
+}
+
+impl PowerSampler {
+    /// Create a sampler with no history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read fresh RAPL counters and battery state.
+    pub fn sample(&mut self) -> PowerUsage {
+        let now = Instant::now();
+        let current = read_rapl_counters(Path::new("/sys/class/powercap"));
+
+        let domains = match &self.previous {
+            Some((previous, then)) => {
+                domains_between(previous, &current, now.duration_since(*then).as_secs_f64())
+            }
+            None => domains_between(&current, &current, 0.0),
+        };
+
+        self.previous = Some((current, now));
+        PowerUsage {
+            domains,
+            batteries: BatteryInfo::read_all(Path::new("/sys/class/power_supply")),
+        }
+    }
+}
+
+/// Compute per-domain power from two counter snapshots taken `elapsed`
+/// seconds apart, allowing for counters that wrapped around in between.
+pub fn domains_between(
+    previous: &[RaplCounter],
+    current: &[RaplCounter],
+    elapsed: f64,
+) -> Vec<PowerDomain> {
+    let previous: HashMap<&str, &RaplCounter> =
+        previous.iter().map(|c| (c.zone.as_str(), c)).collect();
+
+    current
+        .iter()
+        .map(|cur| {
+            let used_uj = previous.get(cur.zone.as_str()).and_then(|prev| {
+                if cur.energy_uj >= prev.energy_uj {
+                    Some(cur.energy_uj - prev.energy_uj)
+                } else if cur.max_energy_range_uj > prev.energy_uj {
+                    // Wrapped: count up to the range limit, then from zero
+                    Some(cur.max_energy_range_uj - prev.energy_uj + cur.energy_uj)
+                } else {
+                    None
+                }
+            });
+
+            let watts = match used_uj {
+                Some(uj) if elapsed > 0.0 => (uj as f64 / 1_000_000.0 / elapsed) as f32,
+                _ => 0.0,
+            };
+
+            PowerDomain { zone: cur.zone.clone(), name: cur.name.clone(), watts }
+        })
+        .collect()
+}
+
+/// Read a trimmed string from a sysfs file.
+fn read_string(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Read an unsigned integer from a sysfs file.
+fn read_number(path: &Path) -> Option<u64> {
+    read_string(path).and_then(|s| s.parse::<u64>().ok())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(zone: &str, name: &str, energy_uj: u64) -> RaplCounter {
+        RaplCounter {
+            zone: zone.to_string(),
+            name: name.to_string(),
+            energy_uj,
+            max_energy_range_uj: 1_000_000_000,
+        }
+    }
+
+    #[test]
+    fn test_domains_between() {
+        let previous = vec![
+            counter("intel-rapl:0", "package-0", 10_000_000),
+            counter("intel-rapl:0:0", "core", 5_000_000),
+        ];
+        let current = vec![
+            counter("intel-rapl:0", "package-0", 30_000_000), // 20 J
+            counter("intel-rapl:0:0", "core", 15_000_000),    // 10 J
+        ];
+
+        let domains = domains_between(&previous, &current, 2.0);
+        assert!((domains[0].watts - 10.0).abs() < 0.01);
+        assert!((domains[1].watts - 5.0).abs() < 0.01);
+        assert_eq!(domains[0].display_name(), "Package 0");
+        assert_eq!(domains[1].display_name(), "Core");
+        assert!(!domains[0].is_subzone());
+        assert!(domains[1].is_subzone());
+    }
+
+    #[test]
+    fn test_domains_between_wraparound() {
+        let previous = vec![counter("intel-rapl:0", "package-0", 999_000_000)];
+        let current = vec![counter("intel-rapl:0", "package-0", 4_000_000)];
+
+        // 1 J to the wrap point plus 4 J after it, over one second
+        let domains = domains_between(&previous, &current, 1.0);
+        assert!((domains[0].watts - 5.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_power_fallback_to_battery() {
+        let mut usage = PowerUsage {
+            domains: Vec::new(),
+            batteries: vec![BatteryInfo {
+                name: "BAT0".to_string(),
+                capacity: Some(80.0),
+                status: "Discharging".to_string(),
+                watts: Some(12.0),
+            }],
+        };
+        assert!((usage.watts() - 12.0).abs() < f32::EPSILON);
+        assert!((usage.percentage(24.0) - 50.0).abs() < 0.01);
+
+        // RAPL package power takes precedence when available
+        usage.domains.push(PowerDomain {
+            zone: "intel-rapl:0".to_string(),
+            name: "package-0".to_string(),
+            watts: 7.5,
+        });
+        assert!((usage.watts() - 7.5).abs() < f32::EPSILON);
+
+        // Charging batteries don't count as draw
+        usage.domains.clear();
+        usage.batteries[0].status = "Charging".to_string();
+        assert_eq!(usage.watts(), 0.0);
+    }
+
+    #[test]
+    fn test_read_fake_sysfs() {
+        let base = std::env::temp_dir()
+            .join(format!("cosmic-runkat-power-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+
+        let package = base.join("powercap/intel-rapl:0");
+        fs::create_dir_all(&package).unwrap();
+        fs::write(package.join("name"), "package-0\n").unwrap();
+        fs::write(package.join("energy_uj"), "123456\n").unwrap();
+        fs::write(package.join("max_energy_range_uj"), "262143328850\n").unwrap();
+        // Root-only counter: no readable energy_uj, so skipped
+        fs::create_dir_all(base.join("powercap/intel-rapl:1")).unwrap();
+        fs::write(base.join("powercap/intel-rapl:1/name"), "psys\n").unwrap();
+
+        let battery = base.join("power_supply/BAT0");
+        fs::create_dir_all(&battery).unwrap();
+        fs::write(battery.join("capacity"), "64\n").unwrap();
+        fs::write(battery.join("status"), "Discharging\n").unwrap();
+        fs::write(battery.join("current_now"), "1000000\n").unwrap(); // 1 A
+        fs::write(battery.join("voltage_now"), "12000000\n").unwrap(); // 12 V
+        fs::create_dir_all(base.join("power_supply/AC")).unwrap();
+
+        let counters = read_rapl_counters(&base.join("powercap"));
+        let batteries = BatteryInfo::read_all(&base.join("power_supply"));
+        fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(counters.len(), 1);
+        assert_eq!(counters[0].name, "package-0");
+        assert_eq!(counters[0].energy_uj, 123456);
+
+        assert_eq!(batteries.len(), 1);
+        assert_eq!(batteries[0].capacity, Some(64.0));
+        assert!(batteries[0].is_discharging());
+        assert!((batteries[0].watts.unwrap() - 12.0).abs() < 0.01);
+    }
+}
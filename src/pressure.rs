@@ -0,0 +1,149 @@
+//! Pressure Stall Information (PSI) module
+//!
+//! Reads `/proc/pressure/{cpu,memory,io}`, which report the share of wall
+//! time in which at least one task was stalled waiting for that resource.
+//! Unlike raw CPU percentage, PSI tells us whether the machine is actually
+//! *waiting* — a 100% busy CPU with nothing queued has no pressure.
+//!
+//! Requires a kernel built with `CONFIG_PSI` (and not booted with
+//! `psi=0`); otherwise every resource reads as `None`.
+//!
+//! ## File format
+//!
+//! ```text
+//! some avg10=1.23 avg60=0.87 avg300=0.40 total=123456789
+//! full avg10=0.00 avg60=0.00 avg300=0.00 total=0
+//! ```
+//!
+//! We only use the `some` line: "at least one task stalled".  The averages
+//! are already percentages over the last 10, 60 and 300 seconds.
+
+use std::fs;
+
+/// Stall averages for one resource, in percent of wall time.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PressureStat {
+    /// Share of the last 10 seconds with at least one stalled task
+    pub avg10: f32,
+    /// Share of the last 60 seconds with at least one stalled task
+    pub avg60: f32,
+    /// Share of the last 300 seconds with at least one stalled task
+    pub avg300: f32,
+}
+
+impl PressureStat {
+    /// Parse the `some` line out of a `/proc/pressure/*` file.
+    pub fn parse(content: &str) -> Option<Self> {
+        let line = content.lines().find(|l| l.starts_with("some "))?;
+
+        let mut stat = Self::default();
+        for field in line.split_whitespace().skip(1) {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            let Ok(value) = value.parse::<f32>() else {
+                continue;
+            };
+            match key {
+                "avg10" => stat.avg10 = value,
+                "avg60" => stat.avg60 = value,
+                "avg300" => stat.avg300 = value,
+                _ => {}
+            }
+        }
+        Some(stat)
+    }
+}
+
+/// PSI readings for CPU, memory and I/O.
+#[derive(Clone, Debug, Default)]
+pub struct PressureInfo {
+    /// CPU pressure — runnable tasks waiting for a CPU
+    pub cpu: Option<PressureStat>,
+    /// Memory pressure — tasks waiting on reclaim, swap-in or refaults
+    pub memory: Option<PressureStat>,
+    /// I/O pressure — tasks waiting on block I/O
+    pub io: Option<PressureStat>,
+}
+
+impl PressureInfo {
+    /// Read all three resources from `/proc/pressure`.
+    pub fn read() -> Self {
+        let read = |resource: &str| {
+            let path = format!("/proc/pressure/{}", resource);
+            fs::read_to_string(&path)
+                .map_err(|e| tracing::debug!("Failed to read {}: {}", path, e))
+                .ok()
+                .and_then(|content| PressureStat::parse(&content))
+        };
+
+        Self {
+            cpu: read("cpu"),
+            memory: read("memory"),
+            io: read("io"),
+        }
+    }
+
+    /// Whether the kernel exposes PSI at all
+    pub fn is_available(&self) -> bool {
+        self.cpu.is_some() || self.memory.is_some() || self.io.is_some()
+    }
+
+    /// The worst `some avg10` across all three resources (0.0 to 100.0).
+    ///
+    /// This is the metric used to drive the animation speed in pressure
+    /// mode — whichever resource is the bottleneck right now.
+    pub fn worst_avg10(&self) -> f32 {
+        [self.cpu, self.memory, self.io]
+            .iter()
+            .flatten()
+            .map(|s| s.avg10)
+            .fold(0.0f32, f32::max)
+            .clamp(0.0, 100.0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pressure_parse() {
+        let content = "\
+some avg10=1.23 avg60=0.87 avg300=0.40 total=123456789
+full avg10=0.50 avg60=0.20 avg300=0.10 total=1234
+";
+        let stat = PressureStat::parse(content).unwrap();
+        assert!((stat.avg10 - 1.23).abs() < f32::EPSILON);
+        assert!((stat.avg60 - 0.87).abs() < f32::EPSILON);
+        assert!((stat.avg300 - 0.40).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_pressure_parse_invalid() {
+        assert_eq!(PressureStat::parse(""), None);
+        assert_eq!(PressureStat::parse("full avg10=1.00"), None);
+    }
+
+    #[test]
+    fn test_pressure_worst_avg10() {
+        let info = PressureInfo {
+            cpu: Some(PressureStat { avg10: 3.0, avg60: 9.0, avg300: 1.0 }),
+            memory: None,
+            io: Some(PressureStat { avg10: 12.5, avg60: 2.0, avg300: 1.0 }),
+        };
+        assert!(info.is_available());
+        assert!((info.worst_avg10() - 12.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_pressure_unavailable() {
+        let info = PressureInfo::default();
+        assert!(!info.is_available());
+        assert_eq!(info.worst_avg10(), 0.0);
+    }
+}
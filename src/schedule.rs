@@ -0,0 +1,318 @@
+//! Schedule module
+//!
+//! Time-of-day schedules that change what the cat does during given hours
+//! on given weekdays: either switch to a profile (e.g. "Quiet" during
+//! working hours) or keep the cat asleep altogether (e.g. during meetings
+//! when the screen is shared).
+//!
+//! Times are local wall-clock times written as `"HH:MM"`.  A schedule whose
+//! end is before its start runs overnight: `22:00`-`07:00` on Friday covers
+//! Friday night until Saturday morning.  If several schedules are active at
+//! once, the first one in the list wins.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Minutes in a day
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// Day of the week.  Stored as a lowercase three-letter name (`"mon"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Weekday {
+    /// All days, Monday first
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ];
+
+    /// The day before this one
+    pub fn previous(self) -> Self {
+        let index = Self::ALL.iter().position(|&d| d == self).unwrap_or(0);
+        Self::ALL[(index + 6) % 7]
+    }
+}
+
+impl From<chrono::Weekday> for Weekday {
+    fn from(day: chrono::Weekday) -> Self {
+        Self::ALL[day.num_days_from_monday() as usize]
+    }
+}
+
+/// A wall-clock time of day, stored as `"HH:MM"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeOfDay {
+    /// Minutes since midnight, 0-1439
+    minutes: u16,
+}
+
+impl TimeOfDay {
+    /// A time from hours (0-23) and minutes (0-59), or None if out of range
+    pub fn new(hours: u16, minutes: u16) -> Option<Self> {
+        (hours < 24 && minutes < 60).then_some(Self { minutes: hours * 60 + minutes })
+    }
+
+    /// Parse `"HH:MM"` (the hour may be a single digit)
+    pub fn parse(text: &str) -> Option<Self> {
+        let (hours, minutes) = text.trim().split_once(':')?;
+        if minutes.len() != 2 {
+            return None;
+        }
+        Self::new(hours.parse().ok()?, minutes.parse().ok()?)
+    }
+}
+
+impl std::fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}", self.minutes / 60, self.minutes % 60)
+    }
+}
+
+impl Serialize for TimeOfDay {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeOfDay {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Self::parse(&text).ok_or_else(|| {
+            serde::de::Error::custom(format!("expected a time as \"HH:MM\", got {text:?}"))
+        })
+    }
+}
+
+/// What a schedule does while it is active.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleAction {
+    /// Keep the cat asleep whatever the metric says (quiet hours)
+    Sleep,
+    /// Switch to the named profile, and back again afterwards
+    Profile(String),
+}
+
+/// A time window on some weekdays and what to do during it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "StoredSchedule", into = "StoredSchedule")]
+pub struct Schedule {
+    /// Name shown in the popup while the schedule is active
+    pub name: String,
+    /// Days the window starts on; empty means every day
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    /// When the window starts
+    pub start: TimeOfDay,
+    /// When the window ends (before `start` for overnight windows; equal to
+    /// it for the whole day)
+    pub end: TimeOfDay,
+    /// What to do during the window
+    pub action: ScheduleAction,
+}
+
+/// The kind of `ScheduleAction`, as stored in `action`
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ActionKind {
+    Sleep,
+    Profile,
+}
+
+/// How a `Schedule` is stored: `"action": "sleep"`, or `"action":
+/// "profile"` with the profile's name in `profile`.  These are plain fields
+/// rather than a flattened `ScheduleAction` because the cosmic-config store
+/// (RON) can't read flattened fields back.
+#[derive(Serialize, Deserialize)]
+struct StoredSchedule {
+    name: String,
+    #[serde(default)]
+    days: Vec<Weekday>,
+    start: TimeOfDay,
+    end: TimeOfDay,
+    action: ActionKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
+}
+
+impl TryFrom<StoredSchedule> for Schedule {
+    type Error = String;
+
+    fn try_from(stored: StoredSchedule) -> Result<Self, String> {
+        let action = match (stored.action, stored.profile) {
+            (ActionKind::Sleep, _) => ScheduleAction::Sleep,
+            (ActionKind::Profile, Some(profile)) => ScheduleAction::Profile(profile),
+            (ActionKind::Profile, None) => {
+                return Err(format!("schedule {:?} has no profile to switch to", stored.name))
+            }
+        };
+        Ok(Self {
+            name: stored.name,
+            days: stored.days,
+            start: stored.start,
+            end: stored.end,
+            action,
+        })
+    }
+}
+
+impl From<Schedule> for StoredSchedule {
+    fn from(schedule: Schedule) -> Self {
+        let (action, profile) = match schedule.action {
+            ScheduleAction::Sleep => (ActionKind::Sleep, None),
+            ScheduleAction::Profile(profile) => (ActionKind::Profile, Some(profile)),
+        };
+        Self {
+            name: schedule.name,
+            days: schedule.days,
+            start: schedule.start,
+            end: schedule.end,
+            action,
+            profile,
+        }
+    }
+}
+
+impl Schedule {
+    /// Whether the schedule is active at a given day and time
+    pub fn is_active(&self, day: Weekday, time: TimeOfDay) -> bool {
+        let on = |day: Weekday| self.days.is_empty() || self.days.contains(&day);
+        if self.start == self.end {
+            on(day)
+        } else if self.start < self.end {
+            on(day) && self.start <= time && time < self.end
+        } else {
+            // Overnight: the part after midnight belongs to the day before
+            (on(day) && time >= self.start) || (on(day.previous()) && time < self.end)
+        }
+    }
+}
+
+/// The first schedule active at a given day and time, if any
+pub fn active_schedule(schedules: &[Schedule], day: Weekday, time: TimeOfDay) -> Option<&Schedule> {
+    schedules.iter().find(|s| s.is_active(day, time))
+}
+
+/// The current local weekday and time of day
+pub fn now() -> (Weekday, TimeOfDay) {
+    use chrono::{Datelike, Timelike};
+
+    let now = chrono::Local::now();
+    let minutes = (now.hour() * 60 + now.minute()) as u16 % MINUTES_PER_DAY;
+    (now.weekday().into(), TimeOfDay { minutes })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(text: &str) -> TimeOfDay {
+        TimeOfDay::parse(text).unwrap()
+    }
+
+    fn schedule(days: Vec<Weekday>, start: &str, end: &str) -> Schedule {
+        Schedule {
+            name: "Test".to_string(),
+            days,
+            start: time(start),
+            end: time(end),
+            action: ScheduleAction::Sleep,
+        }
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(time("9:05").to_string(), "09:05");
+        assert_eq!(time("23:59").to_string(), "23:59");
+        assert!(TimeOfDay::parse("24:00").is_none());
+        assert!(TimeOfDay::parse("12:60").is_none());
+        assert!(TimeOfDay::parse("12:5").is_none());
+        assert!(TimeOfDay::parse("noon").is_none());
+    }
+
+    #[test]
+    fn test_daytime_window() {
+        let s = schedule(vec![Weekday::Mon, Weekday::Tue], "09:00", "17:30");
+        assert!(s.is_active(Weekday::Mon, time("09:00")));
+        assert!(s.is_active(Weekday::Tue, time("17:29")));
+        assert!(!s.is_active(Weekday::Tue, time("17:30")));
+        assert!(!s.is_active(Weekday::Mon, time("08:59")));
+        assert!(!s.is_active(Weekday::Wed, time("12:00")));
+    }
+
+    #[test]
+    fn test_overnight_window() {
+        let s = schedule(vec![Weekday::Fri], "22:00", "07:00");
+        assert!(s.is_active(Weekday::Fri, time("23:00")));
+        assert!(s.is_active(Weekday::Sat, time("06:59")));
+        assert!(!s.is_active(Weekday::Sat, time("07:00")));
+        assert!(!s.is_active(Weekday::Fri, time("06:00")));
+        assert!(!s.is_active(Weekday::Sat, time("23:00")));
+    }
+
+    #[test]
+    fn test_every_day_and_whole_day() {
+        let every_day = schedule(Vec::new(), "12:00", "13:00");
+        assert!(Weekday::ALL.iter().all(|&d| every_day.is_active(d, time("12:30"))));
+
+        let whole_day = schedule(vec![Weekday::Sun], "00:00", "00:00");
+        assert!(whole_day.is_active(Weekday::Sun, time("00:00")));
+        assert!(whole_day.is_active(Weekday::Sun, time("23:59")));
+        assert!(!whole_day.is_active(Weekday::Mon, time("00:30")));
+    }
+
+    #[test]
+    fn test_first_active_schedule_wins() {
+        let mut first = schedule(Vec::new(), "09:00", "10:00");
+        first.name = "First".to_string();
+        let second = schedule(Vec::new(), "00:00", "00:00");
+        let schedules = [first, second];
+        assert_eq!(active_schedule(&schedules, Weekday::Mon, time("09:30")).unwrap().name, "First");
+        assert_eq!(active_schedule(&schedules, Weekday::Mon, time("11:00")).unwrap().name, "Test");
+    }
+
+    #[test]
+    fn test_schedule_json() {
+        let s: Schedule = serde_json::from_str(
+            r#"{"name": "Focus", "days": ["mon", "fri"], "start": "9:00", "end": "12:00",
+                "action": "profile", "profile": "Quiet"}"#,
+        )
+        .unwrap();
+        assert_eq!(s.action, ScheduleAction::Profile("Quiet".to_string()));
+        assert_eq!(s.start, time("09:00"));
+
+        let s: Schedule = serde_json::from_str(
+            r#"{"name": "Meetings", "start": "14:00", "end": "15:00", "action": "sleep"}"#,
+        )
+        .unwrap();
+        assert_eq!(s.action, ScheduleAction::Sleep);
+        assert!(s.days.is_empty());
+        assert!(serde_json::to_string(&s).unwrap().contains(r#""start":"14:00""#));
+
+        assert!(serde_json::from_str::<Schedule>(
+            r#"{"name": "Bad", "start": "25:00", "end": "15:00", "action": "sleep"}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Schedule>(
+            r#"{"name": "Bad", "start": "14:00", "end": "15:00", "action": "profile"}"#
+        )
+        .is_err());
+    }
+}
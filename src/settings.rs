@@ -4,8 +4,8 @@
 //! applet.  Launched as a separate process via `cosmic-runkat --settings`
 //! so it doesn't block the panel applet's event loop.
 //!
-//! Changes are saved to disk immediately and the applet picks them up on
-//! its next config poll cycle (~500ms).
+//! Changes are saved to disk immediately and the applet picks them up as
+//! soon as it sees the file change.
 
 use cosmic::app::Core;
 use cosmic::iced::Length;
@@ -61,7 +61,8 @@ impl Application for SettingsApp {
     fn view(&self) -> Element<'_, Self::Message> {
         let content = settings_page::view(&self.page);
 
-        widget::container(container(content).max_width(800))
+        // Scrollable, since some sources (e.g. the weighted mix) add many rows
+        widget::container(widget::scrollable(container(content).max_width(800)))
             .width(Length::Fill)
             .height(Length::Fill)
             .center_x(Length::Fill)
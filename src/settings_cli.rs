@@ -1,8 +1,18 @@
 //! CLI settings protocol for cosmic-applet-settings hub integration.
 //!
 //! Supports `--settings-describe`, `--settings-set`, and `--settings-action`.
+//! They work on the config of the instance picked at startup (see
+//! `Config::set_instance()`), which the applet passes to the hub through
+//! `RUNKAT_INSTANCE`.
 
-use crate::config::{AnimationSource, Config};
+use crate::config::{
+    format_curve_points, parse_curve_points, AnimationSource, Config, MixMode, SmoothingMethod,
+    SpeedCurve,
+};
+use crate::constants::{
+    MAX_FPS, MAX_INTERVAL_MS, MAX_SMOOTHING_SAMPLES, MAX_SPEED_CURVE_STEPS, MIN_FPS,
+    MIN_INTERVAL_MS,
+};
 
 /// Output the settings schema as JSON to stdout.
 pub fn describe() {
@@ -18,55 +28,347 @@ pub fn describe() {
             AnimationSource::Temperature => {
                 (0.0, 150.0, 1.0, "°C", config.sleep_threshold_temp as f64)
             }
+            AnimationSource::Memory => (0.0, 100.0, 1.0, "%", config.sleep_threshold_mem as f64),
+            AnimationSource::Network => {
+                (0.0, 10000.0, 10.0, " KB/s", config.sleep_threshold_net as f64)
+            }
+            AnimationSource::DiskIo => {
+                (0.0, 100.0, 1.0, "% busy", config.sleep_threshold_disk as f64)
+            }
+            AnimationSource::Gpu => (0.0, 100.0, 1.0, "% busy", config.sleep_threshold_gpu as f64),
+            AnimationSource::Pressure => {
+                (0.0, 25.0, 0.5, "% stalled", config.sleep_threshold_psi as f64)
+            }
+            AnimationSource::LoadAverage => {
+                (0.0, 100.0, 1.0, "% per CPU", config.sleep_threshold_load as f64)
+            }
+            AnimationSource::Power => (0.0, 100.0, 1.0, " W", config.sleep_threshold_power as f64),
+            AnimationSource::Mix => (0.0, 100.0, 1.0, "%", config.sleep_threshold_mix as f64),
+            AnimationSource::Command => (
+                0.0,
+                config.command_full_speed as f64,
+                1.0,
+                "",
+                config.sleep_threshold_command as f64,
+            ),
         };
 
-    let source_value = match config.animation_source {
-        AnimationSource::CpuUsage => "CpuUsage",
-        AnimationSource::Frequency => "Frequency",
-        AnimationSource::Temperature => "Temperature",
-    };
+    let source_options: Vec<serde_json::Value> = AnimationSource::ALL
+        .iter()
+        .map(|&s| serde_json::json!({"value": source_id(s), "label": s.name()}))
+        .collect();
+
+    let mut items = vec![
+        serde_json::json!({
+            "type": "select",
+            "key": "animation_source",
+            "label": "Monitor",
+            "value": source_id(config.animation_source),
+            "options": source_options
+        }),
+        serde_json::json!({
+            "type": "slider",
+            "key": "sleep_threshold",
+            "label": "Sleep Below",
+            "value": threshold_value,
+            "min": threshold_min,
+            "max": threshold_max,
+            "step": threshold_step,
+            "unit": threshold_unit
+        }),
+        serde_json::json!({
+            "type": "slider",
+            "key": "sleep_hysteresis",
+            "label": "Sleep Again Below",
+            "value": config.sleep_hysteresis_pct as f64,
+            "min": 0.0,
+            "max": 50.0,
+            "step": 5.0,
+            "unit": "% under"
+        }),
+        serde_json::json!({
+            "type": "slider",
+            "key": "sleep_min_dwell",
+            "label": "Hold State For",
+            "value": config.sleep_min_dwell_ms as f64 / 1000.0,
+            "min": 0.0,
+            "max": 10.0,
+            "step": 0.5,
+            "unit": " s"
+        }),
+        serde_json::json!({
+            "type": "slider",
+            "key": "network_full_speed",
+            "label": "Full Speed At",
+            "value": config.network_full_speed_mbps as f64,
+            "min": 10.0,
+            "max": 10000.0,
+            "step": 10.0,
+            "unit": " Mbit/s",
+            "visible_when": {"key": "animation_source", "equals": "Network"}
+        }),
+        serde_json::json!({
+            "type": "slider",
+            "key": "power_full_watts",
+            "label": "Full Speed At",
+            "value": config.power_full_watts as f64,
+            "min": 5.0,
+            "max": 300.0,
+            "step": 5.0,
+            "unit": " W",
+            "visible_when": {"key": "animation_source", "equals": "Power"}
+        }),
+        serde_json::json!({
+            "type": "select",
+            "key": "mix_mode",
+            "label": "Combine By",
+            "value": mix_mode_id(config.mix_mode),
+            "options": MixMode::ALL
+                .iter()
+                .zip(MixMode::NAMES)
+                .map(|(&m, name)| serde_json::json!({"value": mix_mode_id(m), "label": name}))
+                .collect::<Vec<_>>(),
+            "visible_when": {"key": "animation_source", "equals": "Mix"}
+        }),
+    ];
 
+    // One weight slider per metric that can take part in the mix
+    for &source in AnimationSource::ALL {
+        if source == AnimationSource::Mix {
+            continue;
+        }
+        items.push(serde_json::json!({
+            "type": "slider",
+            "key": format!("mix_weight.{}", source_id(source)),
+            "label": format!("{} Weight", source.name()),
+            "value": config.mix_weight(source) as f64,
+            "min": 0.0,
+            "max": 2.0,
+            "step": 0.1,
+            "unit": "\u{00d7}",
+            "visible_when": {"key": "animation_source", "equals": "Mix"}
+        }));
+    }
+
+    items.push(serde_json::json!({
+        "type": "text",
+        "key": "cgroup_path",
+        "label": "Only Count Cgroup",
+        "value": config.cgroup_path,
+        "placeholder": "whole system, or e.g. user.slice",
+        "visible_when": {"key": "animation_source", "equals": "CpuUsage"}
+    }));
+    items.push(serde_json::json!({
+        "type": "text",
+        "key": "command_line",
+        "label": "Command",
+        "value": config.command_line,
+        "placeholder": "e.g. ~/bin/queue-depth",
+        "visible_when": {"key": "animation_source", "equals": "Command"}
+    }));
+    items.push(serde_json::json!({
+        "type": "slider",
+        "key": "command_interval",
+        "label": "Run Every",
+        "value": config.command_interval_secs as f64,
+        "min": 1.0,
+        "max": 300.0,
+        "step": 1.0,
+        "unit": " s",
+        "visible_when": {"key": "animation_source", "equals": "Command"}
+    }));
+    items.push(serde_json::json!({
+        "type": "slider",
+        "key": "command_full_speed",
+        "label": "Full Speed At",
+        "value": config.command_full_speed as f64,
+        "min": 1.0,
+        "max": 1000.0,
+        "step": 1.0,
+        "unit": "",
+        "visible_when": {"key": "animation_source", "equals": "Command"}
+    }));
+
+    items.push(serde_json::json!({
+        "type": "toggle",
+        "key": "show_percentage",
+        "label": "Show % on Icon",
+        "value": config.show_percentage,
+        "visible_when": {"key": "animation_source", "equals": "CpuUsage"}
+    }));
+
+    // Speed settings of the selected source: its own, or the defaults
+    let source = config.animation_source;
+    let speed = config.speed_settings(source);
+    let speed_items = vec![
+        serde_json::json!({
+            "type": "toggle",
+            "key": "own_speed",
+            "label": format!("Own Settings for {}", source.name()),
+            "value": config.has_own_speed(source)
+        }),
+        serde_json::json!({
+            "type": "slider",
+            "key": "min_fps",
+            "label": "Slowest",
+            "value": speed.min_fps as f64,
+            "min": MIN_FPS as f64,
+            "max": MAX_FPS as f64,
+            "step": 1.0,
+            "unit": " fps"
+        }),
+        serde_json::json!({
+            "type": "slider",
+            "key": "max_fps",
+            "label": "Fastest",
+            "value": speed.max_fps as f64,
+            "min": MIN_FPS as f64,
+            "max": MAX_FPS as f64,
+            "step": 1.0,
+            "unit": " fps"
+        }),
+        serde_json::json!({
+            "type": "select",
+            "key": "speed_curve",
+            "label": "Speed Curve",
+            "value": speed_curve_id(speed.curve),
+            "options": SpeedCurve::ALL
+                .iter()
+                .zip(SpeedCurve::NAMES)
+                .map(|(&c, name)| serde_json::json!({"value": speed_curve_id(c), "label": name}))
+                .collect::<Vec<_>>()
+        }),
+        serde_json::json!({
+            "type": "slider",
+            "key": "speed_curve_steps",
+            "label": "Steps",
+            "value": speed.curve_steps as f64,
+            "min": 2.0,
+            "max": MAX_SPEED_CURVE_STEPS as f64,
+            "step": 1.0,
+            "unit": "",
+            "visible_when": {"key": "speed_curve", "equals": "Stepped"}
+        }),
+        serde_json::json!({
+            "type": "text",
+            "key": "speed_curve_points",
+            "label": "Points (% : FPS)",
+            "value": format_curve_points(&speed.curve_points),
+            "placeholder": "e.g. 0:2, 10:8, 100:15",
+            "visible_when": {"key": "speed_curve", "equals": "Custom"}
+        }),
+    ];
+
+    let mut sampling_items = vec![
+        serde_json::json!({
+            "type": "slider",
+            "key": "sample_interval",
+            "label": "Sample Every",
+            "value": config.sample_interval_ms as f64,
+            "min": MIN_INTERVAL_MS as f64,
+            "max": MAX_INTERVAL_MS as f64,
+            "step": 100.0,
+            "unit": " ms"
+        }),
+        serde_json::json!({
+            "type": "slider",
+            "key": "smoothing_samples",
+            "label": "Average Over",
+            "value": config.smoothing_samples as f64,
+            "min": 1.0,
+            "max": MAX_SMOOTHING_SAMPLES as f64,
+            "step": 1.0,
+            "unit": " samples"
+        }),
+    ];
+
+    // One smoothing choice per smoothed metric, shown while it's selected
+    for &source in AnimationSource::ALL {
+        let Some(method) = config.smoothing_method(source) else {
+            continue;
+        };
+        sampling_items.push(serde_json::json!({
+            "type": "select",
+            "key": format!("smoothing.{}", source_id(source)),
+            "label": "Smoothing",
+            "value": smoothing_method_id(method),
+            "options": SmoothingMethod::ALL
+                .iter()
+                .zip(SmoothingMethod::NAMES)
+                .map(|(&m, name)| {
+                    serde_json::json!({"value": smoothing_method_id(m), "label": name})
+                })
+                .collect::<Vec<_>>(),
+            "visible_when": {"key": "animation_source", "equals": source_id(source)}
+        }));
+    }
+
+    sampling_items.push(serde_json::json!({
+        "type": "slider",
+        "key": "smoothing_alpha",
+        "label": "Exponential Responsiveness",
+        "value": config.smoothing_alpha as f64,
+        "min": 0.05,
+        "max": 1.0,
+        "step": 0.05,
+        "unit": ""
+    }));
+    sampling_items.push(serde_json::json!({
+        "type": "slider",
+        "key": "smoothing_decay",
+        "label": "Peak Hold Fall Per Sample",
+        "value": config.smoothing_decay as f64,
+        "min": 0.0,
+        "max": 0.95,
+        "step": 0.05,
+        "unit": ""
+    }));
+    sampling_items.push(serde_json::json!({
+        "type": "slider",
+        "key": "config_check_interval",
+        "label": "Check Settings Every",
+        "value": config.config_check_interval_ms as f64,
+        "min": MIN_INTERVAL_MS as f64,
+        "max": MAX_INTERVAL_MS as f64,
+        "step": 100.0,
+        "unit": " ms"
+    }));
+
+    // One action per profile for quick switching
+    let mut actions = vec![
+        serde_json::json!({"id": "reset", "label": "Reset to Defaults", "style": "destructive"}),
+    ];
+    for profile in &config.profiles {
+        let active = if profile.name == config.active_profile { " (active)" } else { "" };
+        actions.push(serde_json::json!({
+            "id": format!("switch-profile:{}", profile.name),
+            "label": format!("Switch to {}{}", profile.name, active),
+            "style": "standard"
+        }));
+    }
+
+    let title = match Config::instance() {
+        Some(id) => format!("RunKat Settings ({id})"),
+        None => "RunKat Settings".to_string(),
+    };
     let schema = serde_json::json!({
-        "title": "RunKat Settings",
+        "title": title,
         "description": "The cat runs faster based on the selected metric.",
         "sections": [
             {
                 "title": "Behavior",
-                "items": [
-                    {
-                        "type": "select",
-                        "key": "animation_source",
-                        "label": "Monitor",
-                        "value": source_value,
-                        "options": [
-                            {"value": "CpuUsage", "label": "CPU Usage"},
-                            {"value": "Frequency", "label": "CPU Frequency"},
-                            {"value": "Temperature", "label": "CPU Temperature"}
-                        ]
-                    },
-                    {
-                        "type": "slider",
-                        "key": "sleep_threshold",
-                        "label": "Sleep Below",
-                        "value": threshold_value,
-                        "min": threshold_min,
-                        "max": threshold_max,
-                        "step": threshold_step,
-                        "unit": threshold_unit
-                    },
-                    {
-                        "type": "toggle",
-                        "key": "show_percentage",
-                        "label": "Show % on Icon",
-                        "value": config.show_percentage,
-                        "visible_when": {"key": "animation_source", "equals": "CpuUsage"}
-                    }
-                ]
+                "items": items
+            },
+            {
+                "title": "Speed",
+                "items": speed_items
+            },
+            {
+                "title": "Sampling",
+                "items": sampling_items
             }
         ],
-        "actions": [
-            {"id": "reset", "label": "Reset to Defaults", "style": "destructive"}
-        ]
+        "actions": actions
     });
 
     println!("{}", serde_json::to_string_pretty(&schema).unwrap());
@@ -79,20 +381,12 @@ pub fn set(key: &str, value: &str) {
     let result = match key {
         "animation_source" => {
             let parsed: Result<String, _> = serde_json::from_str(value);
-            match parsed.as_deref() {
-                Ok("CpuUsage") => {
-                    config.animation_source = AnimationSource::CpuUsage;
+            match parsed.ok().as_deref().and_then(parse_source_id) {
+                Some(source) => {
+                    config.animation_source = source;
                     Ok("Updated animation source")
                 }
-                Ok("Frequency") => {
-                    config.animation_source = AnimationSource::Frequency;
-                    Ok("Updated animation source")
-                }
-                Ok("Temperature") => {
-                    config.animation_source = AnimationSource::Temperature;
-                    Ok("Updated animation source")
-                }
-                _ => Err(format!("Invalid animation_source: {value}")),
+                None => Err(format!("Invalid animation_source: {value}")),
             }
         }
         "sleep_threshold" => match serde_json::from_str::<f64>(value) {
@@ -102,6 +396,197 @@ pub fn set(key: &str, value: &str) {
             }
             Err(e) => Err(format!("Invalid number: {e}")),
         },
+        "sleep_hysteresis" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.sleep_hysteresis_pct = v as f32;
+                Ok("Updated sleep hysteresis")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "sleep_min_dwell" => match serde_json::from_str::<f64>(value) {
+            Ok(v) if v >= 0.0 => {
+                config.sleep_min_dwell_ms = (v * 1000.0).round() as u64;
+                Ok("Updated sleep hold time")
+            }
+            Ok(v) => Err(format!("Invalid hold time: {v}")),
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "network_full_speed" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.network_full_speed_mbps = v as f32;
+                Ok("Updated network full speed")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "power_full_watts" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.power_full_watts = v as f32;
+                Ok("Updated power full speed")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "mix_mode" => {
+            let parsed: Result<String, _> = serde_json::from_str(value);
+            let mode = MixMode::ALL
+                .iter()
+                .copied()
+                .find(|&m| parsed.as_deref().ok() == Some(mix_mode_id(m)));
+            match mode {
+                Some(mode) => {
+                    config.mix_mode = mode;
+                    Ok("Updated mix mode")
+                }
+                None => Err(format!("Invalid mix_mode: {value}")),
+            }
+        }
+        _ if key.starts_with("mix_weight.") => {
+            let source = parse_source_id(&key["mix_weight.".len()..])
+                .filter(|&s| s != AnimationSource::Mix);
+            match (source, serde_json::from_str::<f64>(value)) {
+                (Some(source), Ok(v)) => {
+                    config.set_mix_weight(source, v as f32);
+                    Ok("Updated mix weight")
+                }
+                (None, _) => Err(format!("Unknown key: {key}")),
+                (_, Err(e)) => Err(format!("Invalid number: {e}")),
+            }
+        }
+        "own_speed" => match serde_json::from_str::<bool>(value) {
+            Ok(v) => {
+                config.set_own_speed(config.animation_source, v);
+                Ok("Updated per-source speed settings")
+            }
+            Err(e) => Err(format!("Invalid boolean: {e}")),
+        },
+        "min_fps" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.update_speed(config.animation_source, |speed| speed.min_fps = v as f32);
+                Ok("Updated slowest speed")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "max_fps" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.update_speed(config.animation_source, |speed| speed.max_fps = v as f32);
+                Ok("Updated fastest speed")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "speed_curve" => {
+            let parsed: Result<String, _> = serde_json::from_str(value);
+            let curve = SpeedCurve::ALL
+                .iter()
+                .copied()
+                .find(|&c| parsed.as_deref().ok() == Some(speed_curve_id(c)));
+            match curve {
+                Some(curve) => {
+                    config.update_speed(config.animation_source, |speed| speed.curve = curve);
+                    Ok("Updated speed curve")
+                }
+                None => Err(format!("Invalid speed_curve: {value}")),
+            }
+        }
+        "speed_curve_steps" => match serde_json::from_str::<f64>(value) {
+            Ok(v) if v >= 0.0 => {
+                let steps = v.round() as u32;
+                config.update_speed(config.animation_source, |speed| speed.curve_steps = steps);
+                Ok("Updated speed curve steps")
+            }
+            Ok(v) => Err(format!("Invalid number of steps: {v}")),
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "speed_curve_points" => match serde_json::from_str::<String>(value) {
+            Ok(v) => match parse_curve_points(&v) {
+                Ok(points) => {
+                    config.update_speed(config.animation_source, |speed| {
+                        speed.curve_points = points;
+                    });
+                    Ok("Updated speed curve points")
+                }
+                Err(e) => Err(format!("Invalid speed curve: {e}")),
+            },
+            Err(e) => Err(format!("Invalid string: {e}")),
+        },
+        _ if key.starts_with("smoothing.") => {
+            let source = parse_source_id(&key["smoothing.".len()..])
+                .filter(|&s| config.smoothing_method(s).is_some());
+            let parsed: Result<String, _> = serde_json::from_str(value);
+            let method = SmoothingMethod::ALL
+                .iter()
+                .copied()
+                .find(|&m| parsed.as_deref().ok() == Some(smoothing_method_id(m)));
+            match (source, method) {
+                (Some(source), Some(method)) => {
+                    config.set_smoothing_method(source, method);
+                    Ok("Updated smoothing")
+                }
+                (None, _) => Err(format!("Unknown key: {key}")),
+                (_, None) => Err(format!("Invalid smoothing method: {value}")),
+            }
+        }
+        "smoothing_alpha" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.smoothing_alpha = v as f32;
+                Ok("Updated exponential smoothing")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "smoothing_decay" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.smoothing_decay = v as f32;
+                Ok("Updated peak hold decay")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "cgroup_path" => match serde_json::from_str::<String>(value) {
+            Ok(v) => {
+                config.cgroup_path = v.trim().to_string();
+                Ok("Updated cgroup path")
+            }
+            Err(e) => Err(format!("Invalid string: {e}")),
+        },
+        "command_line" => match serde_json::from_str::<String>(value) {
+            Ok(v) => {
+                config.command_line = v.trim().to_string();
+                Ok("Updated command")
+            }
+            Err(e) => Err(format!("Invalid string: {e}")),
+        },
+        "command_interval" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.command_interval_secs = v as f32;
+                Ok("Updated command interval")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "command_full_speed" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.command_full_speed = v as f32;
+                Ok("Updated command full speed")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "sample_interval" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.sample_interval_ms = v.round() as u64;
+                Ok("Updated sample interval")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "smoothing_samples" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.smoothing_samples = v.round() as usize;
+                Ok("Updated smoothing window")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
+        "config_check_interval" => match serde_json::from_str::<f64>(value) {
+            Ok(v) => {
+                config.config_check_interval_ms = v.round() as u64;
+                Ok("Updated config check interval")
+            }
+            Err(e) => Err(format!("Invalid number: {e}")),
+        },
         "show_percentage" => match serde_json::from_str::<bool>(value) {
             Ok(v) => {
                 config.show_percentage = v;
@@ -125,7 +610,29 @@ pub fn set(key: &str, value: &str) {
 }
 
 /// Execute an action by ID. Prints JSON result to stdout.
+///
+/// Besides `reset`, profiles are managed with `switch-profile:<name>`,
+/// `save-profile:<name>` (save the current settings under that name) and
+/// `delete-profile:<name>`.
 pub fn action(id: &str) {
+    if let Some((verb, name)) = id.split_once(':') {
+        let mut config = Config::load();
+        let result = match verb {
+            "switch-profile" => config.switch_profile(name).map(|()| "Switched profile"),
+            "save-profile" => config.save_profile(name).map(|()| "Saved profile"),
+            "delete-profile" => config.delete_profile(name).map(|()| "Deleted profile"),
+            _ => Err(format!("Unknown action: {id}")),
+        };
+        match result {
+            Ok(msg) => match config.save() {
+                Ok(()) => print_response(true, msg),
+                Err(e) => print_response(false, &format!("Save failed: {e}")),
+            },
+            Err(msg) => print_response(false, &msg),
+        }
+        return;
+    }
+
     match id {
         "reset" => {
             let config = Config::default();
@@ -138,6 +645,59 @@ pub fn action(id: &str) {
     }
 }
 
+/// The identifier the settings hub uses for an animation source
+fn source_id(source: AnimationSource) -> &'static str {
+    match source {
+        AnimationSource::CpuUsage => "CpuUsage",
+        AnimationSource::Frequency => "Frequency",
+        AnimationSource::Temperature => "Temperature",
+        AnimationSource::Memory => "Memory",
+        AnimationSource::Network => "Network",
+        AnimationSource::DiskIo => "DiskIo",
+        AnimationSource::Gpu => "Gpu",
+        AnimationSource::Pressure => "Pressure",
+        AnimationSource::LoadAverage => "LoadAverage",
+        AnimationSource::Power => "Power",
+        AnimationSource::Mix => "Mix",
+        AnimationSource::Command => "Command",
+    }
+}
+
+/// Reverse of `source_id()`
+fn parse_source_id(id: &str) -> Option<AnimationSource> {
+    AnimationSource::ALL.iter().copied().find(|&s| source_id(s) == id)
+}
+
+/// The identifier the settings hub uses for a mix mode
+fn mix_mode_id(mode: MixMode) -> &'static str {
+    match mode {
+        MixMode::Weighted => "Weighted",
+        MixMode::Max => "Max",
+    }
+}
+
+/// The identifier the settings hub uses for a speed curve
+fn speed_curve_id(curve: SpeedCurve) -> &'static str {
+    match curve {
+        SpeedCurve::Linear => "Linear",
+        SpeedCurve::Logarithmic => "Logarithmic",
+        SpeedCurve::Exponential => "Exponential",
+        SpeedCurve::Smoothstep => "Smoothstep",
+        SpeedCurve::Stepped => "Stepped",
+        SpeedCurve::Custom => "Custom",
+    }
+}
+
+/// The identifier the settings hub uses for a smoothing method
+fn smoothing_method_id(method: SmoothingMethod) -> &'static str {
+    match method {
+        SmoothingMethod::MovingAverage => "MovingAverage",
+        SmoothingMethod::Exponential => "Exponential",
+        SmoothingMethod::Median => "Median",
+        SmoothingMethod::PeakHold => "PeakHold",
+    }
+}
+
 fn print_response(ok: bool, message: &str) {
     let resp = serde_json::json!({"ok": ok, "message": message});
     println!("{}", resp);
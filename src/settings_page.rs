@@ -7,48 +7,302 @@
 use cosmic::iced::Length;
 use cosmic::widget::{self, settings, text, toggler};
 use cosmic::Element;
+use std::path::PathBuf;
 
-use crate::config::{AnimationSource, Config};
+use crate::config::{
+    format_curve_points, parse_curve_points, AnimationSource, Config, MixMode, SmoothingMethod,
+    SpeedCurve, SpeedSettings,
+};
+use crate::constants::{
+    MAX_FPS, MAX_INTERVAL_MS, MAX_SMOOTHING_SAMPLES, MAX_SPEED_CURVE_STEPS, MIN_FPS,
+    MIN_INTERVAL_MS,
+};
 
 pub struct State {
     pub config: Config,
     pub max_freq_mhz: f32,
+    /// Command line being edited — only saved when applied, so the applet
+    /// doesn't run every half-typed command
+    pub command_draft: String,
+    /// cgroup path being edited — saved when applied, like the command
+    pub cgroup_draft: String,
+    /// Custom speed curve being edited, as `metric:fps` pairs
+    pub curve_points_draft: String,
+    /// Why the last applied speed curve was rejected, if it was
+    pub curve_points_error: Option<String>,
+    /// What was wrong with the config when it was loaded; cleared once
+    /// the repaired settings are saved
+    pub config_problems: Vec<String>,
+    /// Where loading kept a copy of the rejected config file, if it did
+    pub config_backup: Option<PathBuf>,
+    /// Why the last change couldn't be saved, if it couldn't
+    pub save_error: Option<String>,
+    /// Names of the saved profiles, for the profile dropdown
+    pub profile_names: Vec<String>,
+    /// Name typed in for saving the current settings as a profile
+    pub profile_name_draft: String,
+    /// Why the last profile action failed, if it did
+    pub profile_error: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     SleepThresholdChanged(f32),
+    SleepHysteresisChanged(f32),
+    SleepDwellChanged(f32),
     ShowPercentageToggled(bool),
+    CgroupPathEdited(String),
+    CgroupPathApplied,
     AnimationSourceChanged(AnimationSource),
+    NetworkFullSpeedChanged(f32),
+    PowerFullWattsChanged(f32),
+    MixModeChanged(MixMode),
+    MixWeightChanged(AnimationSource, f32),
+    CommandLineEdited(String),
+    CommandLineApplied,
+    CommandIntervalChanged(f32),
+    CommandFullSpeedChanged(f32),
+    OwnSpeedToggled(bool),
+    MinFpsChanged(f32),
+    MaxFpsChanged(f32),
+    SpeedCurveChanged(SpeedCurve),
+    SpeedCurveStepsChanged(f32),
+    CurvePointsEdited(String),
+    CurvePointsApplied,
+    ProfileSelected(usize),
+    ProfileNameEdited(String),
+    ProfileSaved,
+    ProfileDeleted,
+    SampleIntervalChanged(f32),
+    SmoothingSamplesChanged(f32),
+    SmoothingMethodChanged(SmoothingMethod),
+    SmoothingAlphaChanged(f32),
+    SmoothingDecayChanged(f32),
+    ConfigCheckIntervalChanged(f32),
 }
 
 pub fn init() -> State {
-    let config = Config::load();
+    let (config, config_problems, config_backup) = Config::load_checked();
     let freq_info = crate::sysinfo::CpuFrequency::read();
     let max_freq_mhz = freq_info.max_per_core.first().copied().unwrap_or(5000) as f32;
 
-    State { config, max_freq_mhz }
+    let command_draft = config.command_line.clone();
+    let cgroup_draft = config.cgroup_path.clone();
+    let curve_points_draft =
+        format_curve_points(&config.speed_settings(config.animation_source).curve_points);
+    let profile_names = config.profiles.iter().map(|p| p.name.clone()).collect();
+    let profile_name_draft = config.active_profile.clone();
+
+    State {
+        config,
+        max_freq_mhz,
+        command_draft,
+        cgroup_draft,
+        curve_points_draft,
+        curve_points_error: None,
+        config_problems: config_problems.iter().map(ToString::to_string).collect(),
+        config_backup,
+        save_error: None,
+        profile_names,
+        profile_name_draft,
+        profile_error: None,
+    }
 }
 
 pub fn update(state: &mut State, message: Message) {
     match message {
         Message::SleepThresholdChanged(value) => {
             state.config.set_current_threshold(value);
-            let _ = state.config.save();
+            save(state);
+        }
+        Message::SleepHysteresisChanged(value) => {
+            state.config.sleep_hysteresis_pct = value;
+            save(state);
+        }
+        Message::SleepDwellChanged(value) => {
+            state.config.sleep_min_dwell_ms = (value * 1000.0).round() as u64;
+            save(state);
         }
         Message::ShowPercentageToggled(value) => {
             state.config.show_percentage = value;
-            let _ = state.config.save();
+            save(state);
+        }
+        Message::CgroupPathEdited(value) => {
+            state.cgroup_draft = value;
+        }
+        Message::CgroupPathApplied => {
+            state.config.cgroup_path = state.cgroup_draft.trim().to_string();
+            save(state);
         }
         Message::AnimationSourceChanged(source) => {
             state.config.animation_source = source;
-            let _ = state.config.save();
+            reset_curve_points_draft(state);
+            save(state);
+        }
+        Message::NetworkFullSpeedChanged(value) => {
+            state.config.network_full_speed_mbps = value;
+            save(state);
+        }
+        Message::PowerFullWattsChanged(value) => {
+            state.config.power_full_watts = value;
+            save(state);
+        }
+        Message::MixModeChanged(mode) => {
+            state.config.mix_mode = mode;
+            save(state);
+        }
+        Message::MixWeightChanged(source, weight) => {
+            state.config.set_mix_weight(source, weight);
+            save(state);
+        }
+        Message::CommandLineEdited(value) => {
+            state.command_draft = value;
+        }
+        Message::CommandLineApplied => {
+            state.config.command_line = state.command_draft.trim().to_string();
+            save(state);
+        }
+        Message::CommandIntervalChanged(value) => {
+            state.config.command_interval_secs = value;
+            save(state);
+        }
+        Message::CommandFullSpeedChanged(value) => {
+            state.config.command_full_speed = value;
+            save(state);
+        }
+        // Speed settings apply to the selected source: its own if it has
+        // them, otherwise the defaults shared by every source
+        Message::OwnSpeedToggled(own) => {
+            let source = state.config.animation_source;
+            state.config.set_own_speed(source, own);
+            reset_curve_points_draft(state);
+            save(state);
+        }
+        Message::MinFpsChanged(value) => {
+            let source = state.config.animation_source;
+            state.config.update_speed(source, |speed| {
+                speed.min_fps = value.min(speed.max_fps - 1.0);
+            });
+            save(state);
+        }
+        Message::MaxFpsChanged(value) => {
+            let source = state.config.animation_source;
+            state.config.update_speed(source, |speed| {
+                speed.max_fps = value.max(speed.min_fps + 1.0);
+            });
+            save(state);
+        }
+        Message::SpeedCurveChanged(curve) => {
+            let source = state.config.animation_source;
+            state.config.update_speed(source, |speed| speed.curve = curve);
+            save(state);
+        }
+        Message::SpeedCurveStepsChanged(value) => {
+            let source = state.config.animation_source;
+            state.config.update_speed(source, |speed| speed.curve_steps = value as u32);
+            save(state);
+        }
+        Message::CurvePointsEdited(value) => {
+            state.curve_points_draft = value;
+        }
+        Message::CurvePointsApplied => {
+            let source = state.config.animation_source;
+            let previous = state.config.speed_settings(source).curve_points;
+            state.curve_points_error = match parse_curve_points(&state.curve_points_draft) {
+                Ok(points) => {
+                    state.config.update_speed(source, |speed| speed.curve_points = points);
+                    state.config.save().err().map(|e| e.to_string())
+                }
+                Err(e) => Some(e),
+            };
+            if state.curve_points_error.is_some() {
+                state.config.update_speed(source, |speed| speed.curve_points = previous);
+            } else {
+                reset_curve_points_draft(state);
+            }
+        }
+        Message::ProfileSelected(index) => {
+            let Some(name) = state.profile_names.get(index).cloned() else {
+                return;
+            };
+            state.profile_error = state.config.switch_profile(&name).err();
+            state.profile_name_draft = name;
+            reset_curve_points_draft(state);
+            save(state);
+        }
+        Message::ProfileNameEdited(value) => {
+            state.profile_name_draft = value;
+        }
+        Message::ProfileSaved => {
+            state.profile_error = state.config.save_profile(&state.profile_name_draft).err();
+            reset_profile_names(state);
+            save(state);
+        }
+        Message::ProfileDeleted => {
+            let name = state.config.active_profile.clone();
+            state.profile_error = state.config.delete_profile(&name).err();
+            state.profile_name_draft.clear();
+            reset_profile_names(state);
+            save(state);
+        }
+        Message::SampleIntervalChanged(value) => {
+            state.config.sample_interval_ms = value as u64;
+            save(state);
+        }
+        Message::SmoothingSamplesChanged(value) => {
+            state.config.smoothing_samples = value as usize;
+            save(state);
+        }
+        Message::SmoothingMethodChanged(method) => {
+            let source = state.config.animation_source;
+            state.config.set_smoothing_method(source, method);
+            save(state);
+        }
+        Message::SmoothingAlphaChanged(value) => {
+            state.config.smoothing_alpha = value;
+            save(state);
+        }
+        Message::SmoothingDecayChanged(value) => {
+            state.config.smoothing_decay = value;
+            save(state);
+        }
+        Message::ConfigCheckIntervalChanged(value) => {
+            state.config.config_check_interval_ms = value as u64;
+            save(state);
         }
     }
 }
 
+/// Save the config, remembering why if it couldn't be saved.  A saved
+/// config is valid, so any problems found when loading are gone.
+fn save(state: &mut State) {
+    match state.config.save() {
+        Ok(()) => {
+            state.save_error = None;
+            state.config_problems.clear();
+        }
+        Err(e) => state.save_error = Some(format!("Couldn't save settings: {}", e)),
+    }
+}
+
+/// Refresh the profile dropdown after profiles were added or removed
+fn reset_profile_names(state: &mut State) {
+    state.profile_names = state.config.profiles.iter().map(|p| p.name.clone()).collect();
+}
+
+/// Show the selected source's custom speed curve in the text field
+fn reset_curve_points_draft(state: &mut State) {
+    let speed = state.config.speed_settings(state.config.animation_source);
+    state.curve_points_draft = format_curve_points(&speed.curve_points);
+    state.curve_points_error = None;
+}
+
 pub fn view(state: &State) -> Element<'_, Message> {
-    let page_title = text::title1("RunKat Settings");
+    // Name the instance being edited, if it isn't the default one
+    let page_title = text::title1(match Config::instance() {
+        Some(id) => format!("RunKat Settings ({id})"),
+        None => "RunKat Settings".to_string(),
+    });
 
     let selected_source_index = AnimationSource::ALL
         .iter()
@@ -65,6 +319,17 @@ pub fn view(state: &State) -> Element<'_, Message> {
             AnimationSource::CpuUsage => ("Sleep Below", 0.0..=30.0, "%"),
             AnimationSource::Frequency => ("Sleep Below", 0.0..=state.max_freq_mhz, " MHz"),
             AnimationSource::Temperature => ("Sleep Below", 20.0..=100.0, "\u{00b0}C"),
+            AnimationSource::Memory => ("Sleep Below", 0.0..=100.0, "%"),
+            AnimationSource::Network => ("Sleep Below", 0.0..=1000.0, " KB/s"),
+            AnimationSource::DiskIo => ("Sleep Below", 0.0..=100.0, "% busy"),
+            AnimationSource::Gpu => ("Sleep Below", 0.0..=100.0, "% busy"),
+            AnimationSource::Pressure => ("Sleep Below", 0.0..=25.0, "% stalled"),
+            AnimationSource::LoadAverage => ("Sleep Below", 0.0..=100.0, "% per CPU"),
+            AnimationSource::Power => ("Sleep Below", 0.0..=100.0, " W"),
+            AnimationSource::Mix => ("Sleep Below", 0.0..=100.0, "%"),
+            AnimationSource::Command => {
+                ("Sleep Below", 0.0..=state.config.command_full_speed, "")
+            }
         };
 
     let display_threshold = state
@@ -72,6 +337,51 @@ pub fn view(state: &State) -> Element<'_, Message> {
         .current_threshold()
         .clamp(*threshold_range.start(), *threshold_range.end());
 
+    // Named sets of source, threshold and speed settings
+    let active_profile_index =
+        state.profile_names.iter().position(|name| *name == state.config.active_profile);
+    let mut profiles_section = settings::section().title("Profiles");
+    if !state.profile_names.is_empty() {
+        let mut delete_button = widget::button::destructive("Delete");
+        if active_profile_index.is_some() {
+            delete_button = delete_button.on_press(Message::ProfileDeleted);
+        }
+        profiles_section = profiles_section.add(settings::item(
+            "Profile",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(
+                    widget::dropdown(
+                        &state.profile_names,
+                        active_profile_index,
+                        Message::ProfileSelected,
+                    )
+                    .width(Length::Fixed(150.0)),
+                )
+                .push(delete_button),
+        ));
+    }
+    let mut save_profile_button = widget::button::standard("Save");
+    if !state.profile_name_draft.trim().is_empty() {
+        save_profile_button = save_profile_button.on_press(Message::ProfileSaved);
+    }
+    profiles_section = profiles_section.add(settings::flex_item(
+        "Save Current Settings As",
+        widget::row()
+            .spacing(8)
+            .align_y(cosmic::iced::Alignment::Center)
+            .push(
+                widget::text_input("e.g. Quiet", &state.profile_name_draft)
+                    .on_input(Message::ProfileNameEdited)
+                    .width(Length::Fill),
+            )
+            .push(save_profile_button),
+    ));
+    if let Some(error) = &state.profile_error {
+        profiles_section = profiles_section.add(text::caption(error.clone()));
+    }
+
     let mut behavior_section = settings::section()
         .title("Behavior")
         .add(settings::item("Monitor", source_dropdown))
@@ -95,20 +405,440 @@ pub fn view(state: &State) -> Element<'_, Message> {
                 ),
         ));
 
+    // Damping so the cat doesn't flicker around the threshold
+    let hysteresis = state.config.sleep_hysteresis_pct.clamp(0.0, 50.0);
+    let dwell_secs = (state.config.sleep_min_dwell_ms as f32 / 1000.0).clamp(0.0, 10.0);
+    behavior_section = behavior_section
+        .add(settings::flex_item(
+            "Sleep Again Below",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(text::body(format!("{:.0}% under", hysteresis)))
+                .push(
+                    widget::slider(0.0..=50.0, hysteresis, Message::SleepHysteresisChanged)
+                        .step(5.0)
+                        .width(Length::Fill),
+                ),
+        ))
+        .add(settings::flex_item(
+            "Hold State For",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(text::body(format!("{:.1} s", dwell_secs)))
+                .push(
+                    widget::slider(0.0..=10.0, dwell_secs, Message::SleepDwellChanged)
+                        .step(0.5)
+                        .width(Length::Fill),
+                ),
+        ));
+
+    if state.config.animation_source == AnimationSource::Network {
+        let full_speed = state.config.network_full_speed_mbps.clamp(10.0, 10000.0);
+        behavior_section = behavior_section.add(settings::flex_item(
+            "Full Speed At",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(text::body(format!("{:.0} Mbit/s", full_speed)))
+                .push(
+                    widget::slider(10.0..=10000.0, full_speed, Message::NetworkFullSpeedChanged)
+                        .step(10.0)
+                        .width(Length::Fill),
+                ),
+        ));
+    }
+
+    if state.config.animation_source == AnimationSource::Power {
+        let full_watts = state.config.power_full_watts.clamp(5.0, 300.0);
+        behavior_section = behavior_section.add(settings::flex_item(
+            "Full Speed At",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(text::body(format!("{:.0} W", full_watts)))
+                .push(
+                    widget::slider(5.0..=300.0, full_watts, Message::PowerFullWattsChanged)
+                        .step(5.0)
+                        .width(Length::Fill),
+                ),
+        ));
+    }
+
+    if state.config.animation_source == AnimationSource::Mix {
+        let selected_mode_index = MixMode::ALL
+            .iter()
+            .position(|&m| m == state.config.mix_mode);
+        let mode_dropdown = widget::dropdown(
+            MixMode::NAMES,
+            selected_mode_index,
+            |idx| Message::MixModeChanged(MixMode::ALL[idx]),
+        )
+        .width(Length::Fixed(150.0));
+        behavior_section = behavior_section.add(settings::item("Combine By", mode_dropdown));
+
+        // One weight slider per metric; 0 leaves it out of the mix
+        for &source in AnimationSource::ALL {
+            if source == AnimationSource::Mix {
+                continue;
+            }
+            let weight = state.config.mix_weight(source).clamp(0.0, 2.0);
+            behavior_section = behavior_section.add(settings::flex_item(
+                source.name(),
+                widget::row()
+                    .spacing(8)
+                    .align_y(cosmic::iced::Alignment::Center)
+                    .push(text::body(format!("\u{00d7}{:.1}", weight)))
+                    .push(
+                        widget::slider(0.0..=2.0, weight, move |w| {
+                            Message::MixWeightChanged(source, w)
+                        })
+                        .step(0.1)
+                        .width(Length::Fill),
+                    ),
+            ));
+        }
+    }
+
+    if state.config.animation_source == AnimationSource::Command {
+        let applied = state.command_draft.trim() == state.config.command_line;
+        let mut apply_button = widget::button::standard("Apply");
+        if !applied {
+            apply_button = apply_button.on_press(Message::CommandLineApplied);
+        }
+        behavior_section = behavior_section.add(settings::flex_item(
+            "Command",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(
+                    widget::text_input("e.g. ~/bin/queue-depth", &state.command_draft)
+                        .on_input(Message::CommandLineEdited)
+                        .width(Length::Fill),
+                )
+                .push(apply_button),
+        ));
+
+        let interval = state.config.command_interval_secs.clamp(1.0, 300.0);
+        behavior_section = behavior_section.add(settings::flex_item(
+            "Run Every",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(text::body(format!("{:.0} s", interval)))
+                .push(
+                    widget::slider(1.0..=300.0, interval, Message::CommandIntervalChanged)
+                        .step(1.0)
+                        .width(Length::Fill),
+                ),
+        ));
+
+        let full_speed = state.config.command_full_speed.clamp(1.0, 1000.0);
+        behavior_section = behavior_section.add(settings::flex_item(
+            "Full Speed At",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(text::body(format!("{:.0}", full_speed)))
+                .push(
+                    widget::slider(1.0..=1000.0, full_speed, Message::CommandFullSpeedChanged)
+                        .step(1.0)
+                        .width(Length::Fill),
+                ),
+        ));
+    }
+
     if state.config.animation_source == AnimationSource::CpuUsage {
+        let applied = state.cgroup_draft.trim() == state.config.cgroup_path;
+        let mut apply_button = widget::button::standard("Apply");
+        if !applied {
+            apply_button = apply_button.on_press(Message::CgroupPathApplied);
+        }
+        behavior_section = behavior_section.add(settings::flex_item(
+            "Only Count Cgroup",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(
+                    widget::text_input("whole system, or e.g. user.slice", &state.cgroup_draft)
+                        .on_input(Message::CgroupPathEdited)
+                        .width(Length::Fill),
+                )
+                .push(apply_button),
+        ));
+
         behavior_section = behavior_section.add(settings::item(
             "Show % on Icon",
             toggler(state.config.show_percentage).on_toggle(Message::ShowPercentageToggled),
         ));
     }
 
-    settings::view_column(vec![
+    // How the metric maps onto the animation speed, with a preview.  These
+    // are the selected source's own settings if it has them.
+    let source = state.config.animation_source;
+    let speed = state.config.speed_settings(source);
+    let min_fps = speed.min_fps.clamp(MIN_FPS, MAX_FPS);
+    let max_fps = speed.max_fps.clamp(MIN_FPS, MAX_FPS);
+    let selected_curve_index = SpeedCurve::ALL.iter().position(|&c| c == speed.curve);
+    let curve_dropdown = widget::dropdown(SpeedCurve::NAMES, selected_curve_index, |idx| {
+        Message::SpeedCurveChanged(SpeedCurve::ALL[idx])
+    })
+    .width(Length::Fixed(150.0));
+    let mut speed_section = settings::section()
+        .title("Speed")
+        .add(settings::item(
+            format!("Own Settings for {}", source.name()),
+            toggler(state.config.has_own_speed(source)).on_toggle(Message::OwnSpeedToggled),
+        ))
+        .add(settings::flex_item(
+            "Slowest",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(text::body(format!("{:.0} fps", min_fps)))
+                .push(
+                    widget::slider(MIN_FPS..=MAX_FPS, min_fps, Message::MinFpsChanged)
+                        .step(1.0)
+                        .width(Length::Fill),
+                ),
+        ))
+        .add(settings::flex_item(
+            "Fastest",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(text::body(format!("{:.0} fps", max_fps)))
+                .push(
+                    widget::slider(MIN_FPS..=MAX_FPS, max_fps, Message::MaxFpsChanged)
+                        .step(1.0)
+                        .width(Length::Fill),
+                ),
+        ))
+        .add(settings::item("Speed Curve", curve_dropdown));
+
+    if speed.curve == SpeedCurve::Stepped {
+        let steps = (speed.curve_steps as f32).clamp(2.0, MAX_SPEED_CURVE_STEPS as f32);
+        speed_section = speed_section.add(settings::flex_item(
+            "Steps",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(text::body(format!("{:.0}", steps)))
+                .push(
+                    widget::slider(
+                        2.0..=MAX_SPEED_CURVE_STEPS as f32,
+                        steps,
+                        Message::SpeedCurveStepsChanged,
+                    )
+                    .step(1.0)
+                    .width(Length::Fill),
+                ),
+        ));
+    }
+
+    if speed.curve == SpeedCurve::Custom {
+        let applied = state.curve_points_draft == format_curve_points(&speed.curve_points);
+        let mut apply_button = widget::button::standard("Apply");
+        if !applied {
+            apply_button = apply_button.on_press(Message::CurvePointsApplied);
+        }
+        speed_section = speed_section.add(settings::flex_item(
+            "Points (% : FPS)",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(
+                    widget::text_input("e.g. 0:2, 10:8, 100:15", &state.curve_points_draft)
+                        .on_input(Message::CurvePointsEdited)
+                        .width(Length::Fill),
+                )
+                .push(apply_button),
+        ));
+        if let Some(error) = &state.curve_points_error {
+            speed_section = speed_section.add(text::caption(error.clone()));
+        }
+    }
+
+    speed_section = speed_section.add(curve_preview(&speed));
+
+    // How often the applet samples and reloads, and how much it smooths
+    let interval_range = MIN_INTERVAL_MS as f32..=MAX_INTERVAL_MS as f32;
+    let sample_interval = (state.config.sample_interval_ms as f32)
+        .clamp(*interval_range.start(), *interval_range.end());
+    let smoothing_samples =
+        (state.config.smoothing_samples as f32).clamp(1.0, MAX_SMOOTHING_SAMPLES as f32);
+    let config_check_interval = (state.config.config_check_interval_ms as f32)
+        .clamp(*interval_range.start(), *interval_range.end());
+    let mut sampling_section = settings::section()
+        .title("Sampling")
+        .add(settings::flex_item(
+            "Sample Every",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(text::body(format!("{:.0} ms", sample_interval)))
+                .push(
+                    widget::slider(
+                        interval_range.clone(),
+                        sample_interval,
+                        Message::SampleIntervalChanged,
+                    )
+                    .step(100.0)
+                    .width(Length::Fill),
+                ),
+        ))
+        .add(settings::flex_item(
+            "Average Over",
+            widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(text::body(format!("{:.0} samples", smoothing_samples)))
+                .push(
+                    widget::slider(
+                        1.0..=MAX_SMOOTHING_SAMPLES as f32,
+                        smoothing_samples,
+                        Message::SmoothingSamplesChanged,
+                    )
+                    .step(1.0)
+                    .width(Length::Fill),
+                ),
+        ));
+
+    // Only CPU usage, frequency and temperature are smoothed
+    if let Some(method) = state.config.smoothing_method(source) {
+        let selected_method_index = SmoothingMethod::ALL.iter().position(|&m| m == method);
+        let method_dropdown = widget::dropdown(
+            SmoothingMethod::NAMES,
+            selected_method_index,
+            |idx| Message::SmoothingMethodChanged(SmoothingMethod::ALL[idx]),
+        )
+        .width(Length::Fixed(150.0));
+        sampling_section = sampling_section.add(settings::item("Smoothing", method_dropdown));
+
+        if method == SmoothingMethod::Exponential {
+            let alpha = state.config.smoothing_alpha.clamp(0.05, 1.0);
+            sampling_section = sampling_section.add(settings::flex_item(
+                "Responsiveness",
+                widget::row()
+                    .spacing(8)
+                    .align_y(cosmic::iced::Alignment::Center)
+                    .push(text::body(format!("{:.2}", alpha)))
+                    .push(
+                        widget::slider(0.05..=1.0, alpha, Message::SmoothingAlphaChanged)
+                            .step(0.05)
+                            .width(Length::Fill),
+                    ),
+            ));
+        }
+
+        if method == SmoothingMethod::PeakHold {
+            let decay = state.config.smoothing_decay.clamp(0.0, 0.95);
+            sampling_section = sampling_section.add(settings::flex_item(
+                "Fall Per Sample",
+                widget::row()
+                    .spacing(8)
+                    .align_y(cosmic::iced::Alignment::Center)
+                    .push(text::body(format!("{:.0}%", decay * 100.0)))
+                    .push(
+                        widget::slider(0.0..=0.95, decay, Message::SmoothingDecayChanged)
+                            .step(0.05)
+                            .width(Length::Fill),
+                    ),
+            ));
+        }
+    }
+
+    sampling_section = sampling_section.add(settings::flex_item(
+        "Check Settings Every",
+        widget::row()
+            .spacing(8)
+            .align_y(cosmic::iced::Alignment::Center)
+            .push(text::body(format!("{:.0} ms", config_check_interval)))
+            .push(
+                widget::slider(
+                    interval_range,
+                    config_check_interval,
+                    Message::ConfigCheckIntervalChanged,
+                )
+                .step(100.0)
+                .width(Length::Fill),
+            ),
+    ));
+
+    let mut sections: Vec<Element<'_, Message>> = vec![
         page_title.into(),
         text::caption(
             "The cat runs faster based on the selected metric. Click the panel applet to see details.",
         )
         .into(),
-        behavior_section.into(),
-    ])
-    .into()
+    ];
+
+    // Banner for settings that had to be repaired or couldn't be saved
+    if !state.config_problems.is_empty() || state.save_error.is_some() {
+        let mut problems_section = settings::section().title("Problems");
+        for problem in state.config_problems.iter().chain(&state.save_error) {
+            problems_section = problems_section.add(text::body(problem.clone()));
+        }
+        if !state.config_problems.is_empty() {
+            let note = match &state.config_backup {
+                Some(backup) => format!(
+                    "These settings were reset or adjusted. A copy of the original file was \
+                     kept as {}.",
+                    backup.display()
+                ),
+                None => "These settings were reset or adjusted.".to_string(),
+            };
+            problems_section = problems_section.add(text::caption(note));
+        }
+        sections.push(problems_section.into());
+    }
+
+    sections.push(profiles_section.into());
+    sections.push(behavior_section.into());
+    sections.push(speed_section.into());
+    sections.push(sampling_section.into());
+    settings::view_column(sections).into()
+}
+
+/// Bar chart of the animation speed from 0% to 100% of the metric, so the
+/// effect of the speed curve can be seen before the cat shows it.
+fn curve_preview(speed: &SpeedSettings) -> Element<'static, Message> {
+    use cosmic::iced::widget::{container, horizontal_space, Space};
+    use cosmic::iced::Color;
+
+    const BARS: usize = 21;
+    const GRAPH_HEIGHT: f32 = 60.0;
+
+    let mut bars = widget::row().spacing(2).align_y(cosmic::iced::Alignment::End);
+    for i in 0..BARS {
+        let metric = i as f32 * 100.0 / (BARS - 1) as f32;
+        let fraction = (speed.calculate_fps(metric) / speed.max_fps).clamp(0.0, 1.0);
+        let height = (fraction * GRAPH_HEIGHT).max(1.0);
+        bars = bars.push(
+            container(Space::new(Length::Fill, Length::Fixed(height)))
+                .width(Length::Fill)
+                .style(|theme: &cosmic::Theme| container::Style {
+                    background: Some(cosmic::iced::Background::Color(Color::from(
+                        theme.cosmic().accent_color(),
+                    ))),
+                    ..Default::default()
+                }),
+        );
+    }
+
+    widget::column()
+        .spacing(4)
+        .push(
+            widget::container(bars)
+                .height(Length::Fixed(GRAPH_HEIGHT))
+                .align_y(cosmic::iced::alignment::Vertical::Bottom),
+        )
+        .push(
+            widget::row()
+                .push(text::caption(format!("0%: {:.1} fps", speed.calculate_fps(0.0))))
+                .push(horizontal_space())
+                .push(text::caption(format!("100%: {:.1} fps", speed.calculate_fps(100.0)))),
+        )
+        .into()
 }
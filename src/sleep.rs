@@ -0,0 +1,128 @@
+//! Sleep decision module
+//!
+//! Decides when the cat curls up and when it wakes, from the monitored
+//! metric and its sleep threshold.  A bare `value < threshold` comparison
+//! makes the cat flicker between sleeping and running whenever the metric
+//! hovers around the threshold, so two things damp it:
+//!
+//! - **Hysteresis**: the cat wakes once the metric reaches the threshold,
+//!   but only falls asleep again when it drops a band *below* it.  The
+//!   band is a fraction of the threshold, so it works in any unit (%, MHz,
+//!   degrees, KB/s, ...).
+//! - **Minimum dwell**: once the cat has changed state it stays that way
+//!   for at least this long, however the metric moves.
+
+use std::time::{Duration, Instant};
+
+/// When the cat should sleep, in the monitored source's own units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SleepRule {
+    /// The cat wakes at or above this value
+    pub threshold: f32,
+    /// How far below the threshold (as a fraction of it, 0-1) the value
+    /// must fall before the cat sleeps again
+    pub hysteresis: f32,
+    /// Shortest time the cat stays asleep or awake once it has changed
+    pub min_dwell: Duration,
+}
+
+impl SleepRule {
+    /// The value the metric must fall below for the cat to fall asleep.
+    /// Uses the threshold's magnitude, so the band stays below negative
+    /// thresholds too (a custom command can print anything).
+    pub fn sleep_below(&self) -> f32 {
+        self.threshold - self.threshold.abs() * self.hysteresis.clamp(0.0, 1.0)
+    }
+}
+
+/// Whether the cat is asleep, and since when.
+#[derive(Clone, Copy, Debug)]
+pub struct SleepState {
+    sleeping: bool,
+    /// When the cat last fell asleep or woke up
+    since: Instant,
+}
+
+impl SleepState {
+    /// Start asleep (until there's real data to wake up for)
+    pub fn new(now: Instant) -> Self {
+        Self { sleeping: true, since: now }
+    }
+
+    /// Whether the cat is currently asleep
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    /// Feed in the latest metric value and return whether the cat is now
+    /// asleep.
+    pub fn update(&mut self, value: f32, rule: &SleepRule, now: Instant) -> bool {
+        let wants_sleep = if self.sleeping {
+            value < rule.threshold
+        } else {
+            value < rule.sleep_below()
+        };
+
+        if wants_sleep != self.sleeping
+            && now.saturating_duration_since(self.since) >= rule.min_dwell
+        {
+            self.sleeping = wants_sleep;
+            self.since = now;
+        }
+        self.sleeping
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(hysteresis: f32, min_dwell_ms: u64) -> SleepRule {
+        SleepRule {
+            threshold: 10.0,
+            hysteresis,
+            min_dwell: Duration::from_millis(min_dwell_ms),
+        }
+    }
+
+    /// Feed values one second apart, returning the state after each
+    fn run(rule: &SleepRule, values: &[f32]) -> Vec<bool> {
+        let start = Instant::now();
+        let mut state = SleepState::new(start);
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| state.update(v, rule, start + Duration::from_secs(i as u64 + 1)))
+            .collect()
+    }
+
+    #[test]
+    fn test_no_hysteresis_is_plain_threshold() {
+        let states = run(&rule(0.0, 0), &[5.0, 10.0, 9.9, 12.0]);
+        assert_eq!(states, [true, false, true, false]);
+    }
+
+    #[test]
+    fn test_hysteresis_band() {
+        // Wakes at 10, but only sleeps again below 8
+        let states = run(&rule(0.2, 0), &[9.0, 10.5, 9.0, 8.5, 7.9, 9.5, 10.0]);
+        assert_eq!(states, [true, false, false, false, true, true, false]);
+    }
+
+    #[test]
+    fn test_min_dwell() {
+        // Samples are 1 s apart; each state must be held for 2.5 s
+        let states = run(&rule(0.0, 2500), &[20.0, 20.0, 20.0, 0.0, 0.0, 0.0, 20.0]);
+        assert_eq!(states, [true, true, false, false, false, true, true]);
+    }
+
+    #[test]
+    fn test_sleep_below_negative_threshold() {
+        let rule = SleepRule { threshold: -10.0, hysteresis: 0.5, min_dwell: Duration::ZERO };
+        assert_eq!(rule.sleep_below(), -15.0);
+    }
+}
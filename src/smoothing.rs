@@ -0,0 +1,188 @@
+//! Smoothing module
+//!
+//! Filters a noisy series of readings (CPU usage, frequency, temperature)
+//! into a steadier value for the animation, so the cat doesn't jitter on
+//! every brief spike.  Which filter is used is chosen per animation source
+//! in the config.
+//!
+//! ## Filters
+//!
+//! - **Moving average**: mean of the last `window` samples.  Simple and
+//!   predictable, but lags by half the window.
+//! - **Exponential**: each sample moves the value `alpha` of the way
+//!   towards it.  Reacts quickly with little memory of old samples.
+//! - **Median**: middle of the last `window` samples.  Ignores one-off
+//!   spikes entirely while still following sustained changes.
+//! - **Peak hold**: jumps up to any new peak at once, then lets the value
+//!   fall by `decay` of itself per sample.  Good for "was it busy
+//!   recently?" rather than "how busy is it on average?".
+
+use std::collections::VecDeque;
+
+use crate::constants::MAX_SMOOTHING_SAMPLES;
+
+/// A smoothing filter and its parameters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filter {
+    /// Mean of the last `window` samples
+    MovingAverage { window: usize },
+    /// Exponential moving average; `alpha` is the weight of each new
+    /// sample, from just above 0 (very smooth) to 1 (no smoothing)
+    Exponential { alpha: f32 },
+    /// Median of the last `window` samples
+    Median { window: usize },
+    /// Rise instantly, then fall by `decay` (0-1) of the held value per
+    /// sample
+    PeakHold { decay: f32 },
+}
+
+/// Applies a `Filter` to a series of samples, one at a time.
+///
+/// The recent samples are always kept (up to `MAX_SMOOTHING_SAMPLES`), so
+/// the filter can be changed between samples without losing history.
+#[derive(Clone, Debug, Default)]
+pub struct Smoother {
+    /// Most recent raw samples, oldest first
+    samples: VecDeque<f32>,
+    /// The last smoothed value, or None before the first sample
+    value: Option<f32>,
+}
+
+impl Smoother {
+    /// Create a smoother with no history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sample and return the new smoothed value.
+    pub fn push(&mut self, sample: f32, filter: Filter) -> f32 {
+        self.samples.push_back(sample);
+        while self.samples.len() > MAX_SMOOTHING_SAMPLES {
+            self.samples.pop_front(); // remove oldest sample
+        }
+
+        let value = match (filter, self.value) {
+            (Filter::MovingAverage { window }, _) => {
+                let recent = self.recent(window);
+                recent.iter().sum::<f32>() / recent.len() as f32
+            }
+            (Filter::Median { window }, _) => median(self.recent(window)),
+            (Filter::Exponential { alpha }, Some(previous)) => {
+                previous + alpha.clamp(0.0, 1.0) * (sample - previous)
+            }
+            (Filter::PeakHold { decay }, Some(previous)) => {
+                sample.max(previous * (1.0 - decay.clamp(0.0, 1.0)))
+            }
+            // The first sample is taken as-is
+            (Filter::Exponential { .. } | Filter::PeakHold { .. }, None) => sample,
+        };
+
+        self.value = Some(value);
+        value
+    }
+
+    /// The current smoothed value (0 before the first sample)
+    pub fn value(&self) -> f32 {
+        self.value.unwrap_or(0.0)
+    }
+
+    /// The last `window` samples (at least one, at most all of them)
+    fn recent(&self, window: usize) -> Vec<f32> {
+        let count = window.clamp(1, self.samples.len().max(1));
+        self.samples.iter().rev().take(count).copied().collect()
+    }
+}
+
+/// Median of a set of values (the mean of the middle two for an even
+/// count, 0 for none)
+fn median(mut values: Vec<f32>) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(f32::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed a series through a fresh smoother, returning every output
+    fn run(filter: Filter, series: &[f32]) -> Vec<f32> {
+        let mut smoother = Smoother::new();
+        series.iter().map(|&s| smoother.push(s, filter)).collect()
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 0.001, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn test_moving_average() {
+        let out = run(Filter::MovingAverage { window: 3 }, &[3.0, 6.0, 9.0, 0.0, 0.0, 0.0]);
+        assert_close(&out, &[3.0, 4.5, 6.0, 5.0, 3.0, 0.0]);
+    }
+
+    #[test]
+    fn test_exponential() {
+        let out = run(Filter::Exponential { alpha: 0.5 }, &[10.0, 20.0, 20.0, 0.0]);
+        assert_close(&out, &[10.0, 15.0, 17.5, 8.75]);
+
+        // Alpha 1 is no smoothing at all
+        let out = run(Filter::Exponential { alpha: 1.0 }, &[10.0, 50.0, 5.0]);
+        assert_close(&out, &[10.0, 50.0, 5.0]);
+    }
+
+    #[test]
+    fn test_median_ignores_spikes() {
+        let out = run(Filter::Median { window: 3 }, &[10.0, 10.0, 100.0, 10.0, 12.0]);
+        assert_close(&out, &[10.0, 10.0, 10.0, 10.0, 12.0]);
+
+        // Even counts average the middle two
+        let out = run(Filter::Median { window: 4 }, &[1.0, 2.0, 3.0, 4.0]);
+        assert_close(&out, &[1.0, 1.5, 2.0, 2.5]);
+    }
+
+    #[test]
+    fn test_peak_hold_decay() {
+        let out = run(Filter::PeakHold { decay: 0.5 }, &[10.0, 80.0, 0.0, 0.0, 30.0, 50.0]);
+        assert_close(&out, &[10.0, 80.0, 40.0, 20.0, 30.0, 50.0]);
+    }
+
+    #[test]
+    fn test_switching_filter_keeps_history() {
+        let mut smoother = Smoother::new();
+        for s in [10.0, 20.0, 30.0] {
+            smoother.push(s, Filter::Exponential { alpha: 1.0 });
+        }
+        assert_eq!(smoother.value(), 30.0);
+
+        // The moving average still sees the earlier samples
+        let value = smoother.push(40.0, Filter::MovingAverage { window: 4 });
+        assert!((value - 25.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_history_bounded() {
+        let mut smoother = Smoother::new();
+        for s in 0..(MAX_SMOOTHING_SAMPLES * 2) {
+            smoother.push(s as f32, Filter::MovingAverage { window: usize::MAX });
+        }
+        assert_eq!(smoother.samples.len(), MAX_SMOOTHING_SAMPLES);
+        // Mean of the last MAX_SMOOTHING_SAMPLES values pushed
+        let expected = (MAX_SMOOTHING_SAMPLES as f32 * 3.0 - 1.0) / 2.0;
+        assert!((smoother.value() - expected).abs() < 0.001);
+    }
+}
@@ -12,9 +12,26 @@
 //! - `/sys/devices/system/cpu/cpu{N}/cpufreq/scaling_max_freq` — maximum
 //!   frequency in kHz
 //! - `/sys/class/hwmon/hwmon{N}/` — hardware monitoring devices (temperatures)
+//!
+//! ## Background sampling
+//!
+//! Reading these means dozens of sysfs reads, and some hwmon drivers (ACPI
+//! or embedded-controller backed) can take tens of milliseconds to answer.
+//! `SensorMonitor` does the reads on its own OS thread, like `CpuMonitor`,
+//! and remembers which hwmon directory holds the CPU sensor so the
+//! directory scan only happens once.
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// How long to wait before scanning `/sys/class/hwmon` again when no CPU
+/// sensor was found (or the cached one stopped answering)
+const HWMON_RESCAN_INTERVAL: Duration = Duration::from_secs(30);
 
 // ---------------------------------------------------------------------------
 // CPU Frequency
@@ -146,14 +163,20 @@ impl CpuTemperature {
     ///
     /// Returns the first sensor that provides temperature data.
     pub fn read() -> Self {
-        let hwmon_base = Path::new("/sys/class/hwmon");
-        if !hwmon_base.exists() {
-            return Self::default();
-        }
+        Self::find_sensor().map(|path| Self::read_hwmon(&path)).unwrap_or_default()
+    }
 
-        let Ok(entries) = fs::read_dir(hwmon_base) else {
-            return Self::default();
-        };
+    /// Find the hwmon directory of the CPU temperature sensor, so it can
+    /// be read repeatedly with `read_hwmon()` without scanning again.
+    pub fn find_sensor() -> Option<PathBuf> {
+        Self::find_sensor_in(Path::new("/sys/class/hwmon"))
+    }
+
+    /// Scan a hwmon class directory for the first known CPU sensor driver
+    /// that reports a temperature.  Split out from `find_sensor()` so tests
+    /// can point it at a fake sysfs tree.
+    fn find_sensor_in(hwmon_base: &Path) -> Option<PathBuf> {
+        let entries = fs::read_dir(hwmon_base).ok()?;
 
         for entry in entries.flatten() {
             let hwmon_path = entry.path();
@@ -166,17 +189,19 @@ impl CpuTemperature {
             let name = name.trim();
 
             // Only read from known CPU temperature sensor drivers
-            if matches!(name, "coretemp" | "k10temp" | "zenpower" | "amdgpu")
-                || name.starts_with("cpu")
-            {
-                let result = Self::read_hwmon(&hwmon_path);
-                if !result.per_core.is_empty() || result.package.is_some() {
-                    return result; // found a working sensor
-                }
+            let is_cpu_sensor = matches!(name, "coretemp" | "k10temp" | "zenpower" | "amdgpu")
+                || name.starts_with("cpu");
+            if is_cpu_sensor && Self::read_hwmon(&hwmon_path).has_readings() {
+                return Some(hwmon_path); // found a working sensor
             }
         }
 
-        Self::default()
+        None
+    }
+
+    /// Whether any temperature was read at all
+    pub fn has_readings(&self) -> bool {
+        !self.per_core.is_empty() || self.package.is_some()
     }
 
     /// Read temperatures from a specific hwmon device directory.
@@ -272,9 +297,116 @@ impl CpuTemperature {
     /// If no critical threshold is known, assumes 100 C.
     /// Used to drive the animation speed in temperature mode.
     pub fn percentage(&self) -> f32 {
-        let max_temp = self.max_temp();
+        self.percentage_of(self.max_temp())
+    }
+
+    /// Get any temperature (e.g. a smoothed one) as a percentage of this
+    /// sensor's critical threshold, or of 100 C if none is known.
+    pub fn percentage_of(&self, temp: f32) -> f32 {
         let critical = self.critical.unwrap_or(100.0);
-        ((max_temp / critical) * 100.0).clamp(0.0, 100.0)
+        ((temp / critical) * 100.0).clamp(0.0, 100.0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Background sampling
+// ---------------------------------------------------------------------------
+
+/// Frequency and temperature read at the same moment.
+#[derive(Clone, Debug, Default)]
+pub struct SensorSample {
+    pub frequency: CpuFrequency,
+    pub temperature: CpuTemperature,
+}
+
+/// Reads CPU frequency and temperature on a background thread and
+/// provides each new sample via a `watch` channel.
+///
+/// Works like `CpuMonitor`: call `start()` once, then `subscribe()` to be
+/// notified of new samples.  The thread is stopped when the monitor is
+/// dropped.
+pub struct SensorMonitor {
+    /// Sender side of the watch channel (cloned into the background thread)
+    tx: watch::Sender<SensorSample>,
+    /// Receiver side — cloned for each subscriber
+    rx: watch::Receiver<SensorSample>,
+    /// Shared flag to tell the background thread to stop
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl SensorMonitor {
+    /// Create a new monitor.  Call `start()` to begin sampling.
+    pub fn new() -> Self {
+        let (tx, rx) = watch::channel(SensorSample::default());
+        Self {
+            tx,
+            rx,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Spawn a background OS thread that reads the sensors immediately and
+    /// then once per `sample_interval`.
+    pub fn start(&self, sample_interval: Duration) {
+        let tx = self.tx.clone();
+        let stop_flag = self.stop_flag.clone();
+
+        thread::spawn(move || {
+            // The hwmon directory of the CPU sensor, found once and reused
+            let mut sensor: Option<PathBuf> = None;
+            let mut last_scan: Option<Instant> = None;
+
+            while !stop_flag.load(Ordering::Relaxed) {
+                if sensor.is_none() && last_scan.is_none_or(|t| t.elapsed() >= HWMON_RESCAN_INTERVAL)
+                {
+                    sensor = CpuTemperature::find_sensor();
+                    last_scan = Some(Instant::now());
+                }
+
+                let temperature = match &sensor {
+                    Some(path) => CpuTemperature::read_hwmon(path),
+                    None => CpuTemperature::default(),
+                };
+                // hwmon numbering can change (e.g. a driver reloading), so
+                // forget a sensor that stops answering and scan again
+                if sensor.is_some() && !temperature.has_readings() {
+                    tracing::debug!("CPU temperature sensor stopped answering; rescanning");
+                    sensor = None;
+                }
+
+                let sample = SensorSample { frequency: CpuFrequency::read(), temperature };
+                if let Err(e) = tx.send(sample) {
+                    tracing::error!("Failed to send sensor update: {}", e);
+                }
+
+                thread::sleep(sample_interval);
+            }
+        });
+    }
+
+    /// Signal the background thread to stop.  It will exit after its
+    /// current sleep.
+    pub fn stop(&self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Get a receiver that is notified each time the background thread
+    /// sends a new sample.  This never blocks.
+    pub fn subscribe(&self) -> watch::Receiver<SensorSample> {
+        self.rx.clone()
+    }
+}
+
+impl Default for SensorMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Automatically stop the sampling thread when the monitor is dropped.
+impl Drop for SensorMonitor {
+    fn drop(&mut self) {
+        self.stop();
     }
 }
 
@@ -359,6 +491,45 @@ mod tests {
         assert!((temp.percentage() - 60.0).abs() < 0.1);
     }
 
+    #[test]
+    fn test_find_sensor_in() {
+        let base = std::env::temp_dir()
+            .join(format!("cosmic-runkat-hwmon-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+
+        // An ACPI zone (not a CPU driver) and an Intel coretemp sensor
+        fs::create_dir_all(base.join("hwmon0")).unwrap();
+        fs::write(base.join("hwmon0/name"), "acpitz\n").unwrap();
+        fs::write(base.join("hwmon0/temp1_input"), "30000\n").unwrap();
+        fs::create_dir_all(base.join("hwmon4")).unwrap();
+        fs::write(base.join("hwmon4/name"), "coretemp\n").unwrap();
+        fs::write(base.join("hwmon4/temp1_input"), "52000\n").unwrap();
+        fs::write(base.join("hwmon4/temp1_label"), "Package id 0\n").unwrap();
+        fs::write(base.join("hwmon4/temp2_input"), "49000\n").unwrap();
+        fs::write(base.join("hwmon4/temp2_label"), "Core 0\n").unwrap();
+
+        let sensor = CpuTemperature::find_sensor_in(&base);
+        let temp = sensor.as_deref().map(CpuTemperature::read_hwmon).unwrap_or_default();
+        let missing = CpuTemperature::find_sensor_in(&base.join("nonexistent"));
+        fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(sensor, Some(base.join("hwmon4")));
+        assert_eq!(temp.package, Some(52.0));
+        assert_eq!(temp.per_core, vec![49.0]);
+        assert!(missing.is_none());
+    }
+
+    #[test]
+    fn test_sensor_monitor_sends_first_sample() {
+        let monitor = SensorMonitor::new();
+        let mut rx = monitor.subscribe();
+        monitor.start(Duration::from_secs(60));
+        thread::sleep(Duration::from_millis(200));
+        // The first sample is sent straight away, not after one interval
+        assert!(rx.has_changed().unwrap());
+        rx.borrow_and_update();
+    }
+
     #[test]
     fn test_temperature_max_temp() {
         let temp = CpuTemperature {
@@ -38,6 +38,19 @@ fn cosmic_config_dir() -> Result<std::path::PathBuf, Box<dyn std::error::Error>>
         .join(".config/cosmic"))
 }
 
+/// Directories holding the COSMIC theme files this module reads (the
+/// dark/light mode switch and both themes), for watching for changes.
+/// Empty if the home directory is unknown.
+pub fn theme_dirs() -> Vec<std::path::PathBuf> {
+    let Ok(config_dir) = cosmic_config_dir() else {
+        return Vec::new();
+    };
+    ["Mode", "Dark", "Light"]
+        .iter()
+        .map(|name| config_dir.join(format!("com.system76.CosmicTheme.{}/v1", name)))
+        .collect()
+}
+
 /// Parse theme colors from COSMIC config files on disk
 fn try_manual_theme_parsing() -> Result<ThemeColors, Box<dyn std::error::Error>> {
     let config_dir = cosmic_config_dir()?;
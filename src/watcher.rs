@@ -0,0 +1,263 @@
+//! Config and theme file watching
+//!
+//! Tells the applet when `config.json` or the COSMIC theme files change, so
+//! it only re-reads them when there is something new instead of polling the
+//! disk twice a second forever.
+//!
+//! ## How it works
+//!
+//! A `notify` watcher (inotify on Linux) watches the *directories* holding
+//! the files rather than the files themselves: the settings window and
+//! COSMIC may replace a file by writing a temp file and renaming it over the
+//! old one, which a watch on the old file would never see.  A theme
+//! directory that doesn't exist yet (on a fresh account COSMIC creates it on
+//! the first theme change) is waited for by watching its nearest existing
+//! parent, and watched itself once it appears.
+//!
+//! Editors and the settings sliders often produce a burst of events for one
+//! change, so events are debounced on a background thread: once things have
+//! been quiet for `CONFIG_DEBOUNCE`, the burst is reported as one change
+//! through a tokio `watch` channel, like `CpuMonitor` does for samples.
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::thread;
+use tokio::sync::watch;
+
+use crate::config::Config;
+use crate::constants::CONFIG_DEBOUNCE;
+use crate::theme;
+
+/// Counts of the changes seen so far.  Compare with the previous value to
+/// tell what changed: a count that went up means that file was modified.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConfigChanges {
+    /// Number of (debounced) changes to `config.json`
+    pub config: u64,
+    /// Number of (debounced) changes to the COSMIC theme files
+    pub theme: u64,
+}
+
+/// Which kind of file an event was about
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Changed {
+    Config,
+    Theme,
+    /// A directory on the way to a theme directory was created
+    ThemeDir,
+}
+
+/// Watches the config file and theme directories on a background thread.
+///
+/// Call `subscribe()` to be notified of changes.  Watching stops when the
+/// watcher is dropped.
+pub struct ConfigWatcher {
+    /// Receiver side — cloned for each subscriber
+    rx: watch::Receiver<ConfigChanges>,
+    /// The OS-level watch; dropping it ends the debounce thread too.  The
+    /// thread only holds a weak reference, to add theme directories that
+    /// appear later.
+    _watcher: Arc<Mutex<RecommendedWatcher>>,
+}
+
+impl ConfigWatcher {
+    /// Start watching.  Fails if the OS watch can't be set up (e.g. the
+    /// inotify watch limit is used up) — the caller should fall back to
+    /// polling.
+    pub fn new() -> notify::Result<Self> {
+        Self::watch_paths(&Config::config_path(), theme::theme_dirs())
+    }
+
+    /// Watch a given config file and theme directories (missing theme
+    /// directories are watched once they appear).
+    fn watch_paths(config_path: &Path, theme_dirs: Vec<PathBuf>) -> notify::Result<Self> {
+        let config_dir = config_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let config_name = config_path.file_name().map(|n| n.to_os_string());
+
+        // The directory must exist to be watched; it's created on first save
+        // anyway
+        if let Err(e) = fs::create_dir_all(&config_dir) {
+            tracing::warn!("Failed to create {}: {}", config_dir.display(), e);
+        }
+
+        let (event_tx, event_rx) = mpsc::channel::<Changed>();
+        let watched_theme_dirs = theme_dirs.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                let event = match res {
+                    Ok(event) => event,
+                    Err(e) => {
+                        tracing::warn!("Config watch error: {}", e);
+                        return;
+                    }
+                };
+                // Reads show up as access events; only changes matter
+                if matches!(event.kind, EventKind::Access(_)) {
+                    return;
+                }
+                for path in &event.paths {
+                    let changed = if path.file_name().map(|n| n.to_os_string()) == config_name
+                        && path.parent() == Some(config_dir.as_path())
+                    {
+                        Changed::Config
+                    } else if watched_theme_dirs.iter().any(|dir| path.parent() == Some(dir)) {
+                        Changed::Theme
+                    } else if watched_theme_dirs.iter().any(|dir| dir.starts_with(path)) {
+                        Changed::ThemeDir
+                    } else {
+                        continue;
+                    };
+                    // Only fails once the debounce thread has gone
+                    let _ = event_tx.send(changed);
+                }
+            })?;
+
+        if let Some(dir) = config_path.parent() {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        }
+        let mut watched = Vec::new();
+        watch_theme_dirs(&mut watcher, &theme_dirs, &mut watched);
+
+        let watcher = Arc::new(Mutex::new(watcher));
+        let thread_watcher = Arc::downgrade(&watcher);
+        let (tx, rx) = watch::channel(ConfigChanges::default());
+        thread::spawn(move || {
+            // Ends when the watcher (and with it `event_tx`) is dropped
+            while let Ok(first) = event_rx.recv() {
+                let mut config = first == Changed::Config;
+                let mut theme = first != Changed::Config;
+                let mut theme_dir = first == Changed::ThemeDir;
+
+                // Swallow the rest of the burst until things go quiet
+                while let Ok(next) = event_rx.recv_timeout(CONFIG_DEBOUNCE) {
+                    config |= next == Changed::Config;
+                    theme |= next != Changed::Config;
+                    theme_dir |= next == Changed::ThemeDir;
+                }
+
+                // Watch whatever appeared.  Files may already have been
+                // written into a new theme directory before its watch was
+                // added, so this counts as a theme change either way.
+                if theme_dir {
+                    let Some(watcher) = thread_watcher.upgrade() else {
+                        break;
+                    };
+                    let mut guard = watcher.lock().unwrap_or_else(PoisonError::into_inner);
+                    watch_theme_dirs(&mut guard, &theme_dirs, &mut watched);
+                }
+
+                tx.send_modify(|changes| {
+                    changes.config += u64::from(config);
+                    changes.theme += u64::from(theme);
+                });
+            }
+        });
+
+        Ok(Self { rx, _watcher: watcher })
+    }
+
+    /// Get a receiver that is notified after each debounced change.
+    pub fn subscribe(&self) -> watch::Receiver<ConfigChanges> {
+        self.rx.clone()
+    }
+}
+
+/// Watch each theme directory, or its nearest existing parent while it
+/// doesn't exist yet.  `watched` holds what is already watched, so calling
+/// this again after a directory appears only adds the new watches.
+fn watch_theme_dirs(
+    watcher: &mut RecommendedWatcher,
+    theme_dirs: &[PathBuf],
+    watched: &mut Vec<PathBuf>,
+) {
+    // A directory that was removed lost its watch; it is re-added if it
+    // comes back
+    watched.retain(|dir| dir.is_dir());
+    for dir in theme_dirs {
+        let Some(target) = dir.ancestors().find(|p| p.is_dir()) else {
+            continue;
+        };
+        if watched.iter().any(|w| w == target) {
+            continue;
+        }
+        match watcher.watch(target, RecursiveMode::NonRecursive) {
+            Ok(()) => watched.push(target.to_path_buf()),
+            // A theme directory that can't be watched just means no theme
+            // updates
+            Err(e) => tracing::warn!("Failed to watch {}: {}", target.display(), e),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_watch_debounces_changes() {
+        let base = std::env::temp_dir().join(format!("runkat-watch-test-{}", std::process::id()));
+        let config_dir = base.join("cosmic-runkat");
+        let theme_dir = base.join("theme");
+        fs::create_dir_all(&theme_dir).unwrap();
+        let config_path = config_dir.join("config.json");
+
+        let watcher = ConfigWatcher::watch_paths(&config_path, vec![theme_dir.clone()]).unwrap();
+        let mut rx = watcher.subscribe();
+
+        // A burst of writes, including an atomic replace, is one change
+        fs::write(&config_path, "{}").unwrap();
+        fs::write(&config_path, "{ }").unwrap();
+        fs::write(config_dir.join("config.json.tmp"), "{}").unwrap();
+        fs::rename(config_dir.join("config.json.tmp"), &config_path).unwrap();
+        thread::sleep(CONFIG_DEBOUNCE * 4);
+        assert_eq!(*rx.borrow_and_update(), ConfigChanges { config: 1, theme: 0 });
+
+        fs::write(theme_dir.join("background"), "(on: (red: 1.0))").unwrap();
+        thread::sleep(CONFIG_DEBOUNCE * 4);
+        assert_eq!(*rx.borrow_and_update(), ConfigChanges { config: 1, theme: 1 });
+
+        // Unrelated files in the config directory are ignored
+        fs::write(config_dir.join("other.json"), "{}").unwrap();
+        thread::sleep(CONFIG_DEBOUNCE * 4);
+        assert!(!rx.has_changed().unwrap());
+
+        drop(watcher);
+        thread::sleep(Duration::from_millis(50));
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_watch_theme_dir_created_later() {
+        let base =
+            std::env::temp_dir().join(format!("runkat-watch-later-test-{}", std::process::id()));
+        let config_path = base.join("cosmic-runkat").join("config.json");
+        let theme_dir = base.join("CosmicTheme.Dark").join("v1");
+
+        let watcher = ConfigWatcher::watch_paths(&config_path, vec![theme_dir.clone()]).unwrap();
+        let mut rx = watcher.subscribe();
+
+        // The theme directory (and its parent) only appear on the first
+        // theme change
+        fs::create_dir_all(&theme_dir).unwrap();
+        fs::write(theme_dir.join("accent"), "(red: 1.0)").unwrap();
+        thread::sleep(CONFIG_DEBOUNCE * 4);
+        let first = rx.borrow_and_update().theme;
+        assert!(first >= 1);
+
+        // From then on it is watched directly
+        fs::write(theme_dir.join("accent"), "(red: 0.5)").unwrap();
+        thread::sleep(CONFIG_DEBOUNCE * 4);
+        assert!(rx.borrow_and_update().theme > first);
+
+        drop(watcher);
+        thread::sleep(Duration::from_millis(50));
+        let _ = fs::remove_dir_all(&base);
+    }
+}